@@ -0,0 +1,248 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::Bytes32;
+use commit_verify::{Digest, DigestExt, Sha256};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+use strict_types::StrictVal;
+
+use crate::{Attachment, MediaType, LIB_NAME_RGB21, LIB_NAME_RGB_CONTRACT};
+
+/// Tag used for computing attachment digests with [`DigestAlgo::TaggedSha256`].
+pub const ATTACHMENT_DIGEST_TAG: &str = "urn:lnp-bp:rgb21:attachment#2026-10-18";
+
+/// Hash function used to compute the digest of an attachment or another external document.
+///
+/// The type belongs to the `RGBContract` library, so contract-level types (like
+/// [`crate::ContractTerms`]) can reference documents in the same way as RGB21 attachments.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[repr(u8)]
+pub enum DigestAlgo {
+    /// Single SHA-256 of the attachment data. This is the algorithm implicitly used by the
+    /// [`Attachment`] type.
+    #[default]
+    #[display("sha256")]
+    Sha256 = 0,
+
+    /// BLAKE3 with 32-byte output.
+    #[display("blake3")]
+    Blake3 = 1,
+
+    /// Tagged SHA-256 in the `commit_verify` style, using [`ATTACHMENT_DIGEST_TAG`].
+    #[display("tagged-sha256")]
+    TaggedSha256 = 2,
+}
+impl StrictSerialize for DigestAlgo {}
+impl StrictDeserialize for DigestAlgo {}
+
+impl DigestAlgo {
+    pub fn from_strict_val_unchecked(value: &StrictVal) -> Self { value.unwrap_enum() }
+
+    /// Computes digest of the provided data using the hash function.
+    pub fn digest(self, data: impl AsRef<[u8]>) -> Bytes32 {
+        let data = data.as_ref();
+        match self {
+            DigestAlgo::Sha256 => Bytes32::from_byte_array(Sha256::digest(data)),
+            DigestAlgo::Blake3 => Bytes32::from_byte_array(*blake3::hash(data).as_bytes()),
+            DigestAlgo::TaggedSha256 => Bytes32::from_byte_array(
+                Sha256::from_tag(ATTACHMENT_DIGEST_TAG)
+                    .with_raw(data)
+                    .finish(),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum AttachmentError {
+    /// attachment data do not match the committed {algo} digest {expected} (actual digest is
+    /// {actual}).
+    DigestMismatch {
+        algo: DigestAlgo,
+        expected: Bytes32,
+        actual: Bytes32,
+    },
+
+    /// attachment digest uses {0} hash function, while the legacy attachment format supports only
+    /// SHA-256.
+    UnsupportedAlgo(DigestAlgo),
+}
+
+/// Attachment which explicitly specifies the hash function used to produce its digest.
+///
+/// Unlike [`Attachment`], which always implies SHA-256, this form allows issuers to use other hash
+/// functions without changing the meaning of the existing data. A legacy attachment converts into
+/// this form losslessly.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct AttachmentV1 {
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::rust::display_fromstr"))]
+    pub mime: MediaType,
+    pub algo: DigestAlgo,
+    pub digest: Bytes32,
+}
+impl StrictSerialize for AttachmentV1 {}
+impl StrictDeserialize for AttachmentV1 {}
+
+impl From<Attachment> for AttachmentV1 {
+    fn from(attachment: Attachment) -> Self {
+        AttachmentV1 {
+            mime: attachment.mime,
+            algo: DigestAlgo::Sha256,
+            digest: attachment.digest,
+        }
+    }
+}
+
+impl TryFrom<AttachmentV1> for Attachment {
+    type Error = AttachmentError;
+
+    fn try_from(attachment: AttachmentV1) -> Result<Self, Self::Error> {
+        if attachment.algo != DigestAlgo::Sha256 {
+            return Err(AttachmentError::UnsupportedAlgo(attachment.algo));
+        }
+        Ok(Attachment { mime: attachment.mime, digest: attachment.digest })
+    }
+}
+
+impl AttachmentV1 {
+    /// Constructs attachment by hashing the provided data with the given hash function.
+    pub fn with_data(mime: MediaType, algo: DigestAlgo, data: impl AsRef<[u8]>) -> Self {
+        let digest = algo.digest(data);
+        AttachmentV1 { mime, algo, digest }
+    }
+
+    /// Checks that the provided data match the attachment digest.
+    pub fn verify(&self, data: impl AsRef<[u8]>) -> Result<(), AttachmentError> {
+        let actual = self.algo.digest(data);
+        if actual != self.digest {
+            return Err(AttachmentError::DigestMismatch { algo: self.algo, expected: self.digest, actual });
+        }
+        Ok(())
+    }
+
+    pub fn from_strict_val_unchecked(value: &StrictVal) -> Self {
+        let mime = MediaType::from_strict_val_unchecked(value.unwrap_struct("mime"));
+        let algo = DigestAlgo::from_strict_val_unchecked(value.unwrap_struct("algo"));
+        let digest = value
+            .unwrap_struct("digest")
+            .unwrap_bytes()
+            .try_into()
+            .expect("invalid digest");
+        Self { mime, algo, digest }
+    }
+}
+
+impl Attachment {
+    /// Constructs attachment by computing SHA-256 digest of the provided data.
+    pub fn with_data(mime: MediaType, data: impl AsRef<[u8]>) -> Self {
+        let digest = DigestAlgo::Sha256.digest(data);
+        Attachment { mime, digest }
+    }
+
+    /// Checks that the provided data match the attachment SHA-256 digest.
+    pub fn verify(&self, data: impl AsRef<[u8]>) -> Result<(), AttachmentError> {
+        AttachmentV1::from(self.clone()).verify(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use amplify::hex::{FromHex, ToHex};
+
+    use super::*;
+
+    #[test]
+    fn sha256_vector() {
+        assert_eq!(
+            DigestAlgo::Sha256.digest(b"abc").to_string(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn blake3_vector() {
+        assert_eq!(
+            DigestAlgo::Blake3.digest(b"").to_string(),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test]
+    fn algos_differ() {
+        let sha = DigestAlgo::Sha256.digest(b"data");
+        let tagged = DigestAlgo::TaggedSha256.digest(b"data");
+        let blake = DigestAlgo::Blake3.digest(b"data");
+        assert_ne!(sha, tagged);
+        assert_ne!(sha, blake);
+        assert_ne!(tagged, blake);
+    }
+
+    #[test]
+    fn verify() {
+        let mime = MediaType::with("image/png");
+        for algo in [DigestAlgo::Sha256, DigestAlgo::Blake3, DigestAlgo::TaggedSha256] {
+            let attachment = AttachmentV1::with_data(mime.clone(), algo, b"image data");
+            assert_eq!(attachment.verify(b"image data"), Ok(()));
+            assert!(matches!(
+                attachment.verify(b"other data"),
+                Err(AttachmentError::DigestMismatch { algo: a, .. }) if a == algo
+            ));
+        }
+    }
+
+    #[test]
+    fn legacy_conversion() {
+        let legacy = Attachment::with_data(MediaType::with("text/plain"), b"hello");
+        assert_eq!(legacy.verify(b"hello"), Ok(()));
+
+        let v1 = AttachmentV1::from(legacy.clone());
+        assert_eq!(v1.algo, DigestAlgo::Sha256);
+        assert_eq!(v1.verify(b"hello"), Ok(()));
+        assert_eq!(Attachment::try_from(v1), Ok(legacy));
+
+        let blake = AttachmentV1::with_data(MediaType::with("text/plain"), DigestAlgo::Blake3, b"hello");
+        assert_eq!(Attachment::try_from(blake), Err(AttachmentError::UnsupportedAlgo(DigestAlgo::Blake3)));
+    }
+
+    #[test]
+    fn legacy_encoding_decodes() {
+        // `Attachment { mime: "image/jpeg", digest: sha256("jpeg") }` as encoded before the
+        // introduction of `AttachmentV1`.
+        const LEGACY: &str =
+            "05696d61676501046a7065670041e5787e9f28562d07b891b1816b492309d646c0f2829743fa4963a9f9cc1d61";
+        let legacy = Attachment::with_data(MediaType::with("image/jpeg"), b"jpeg");
+        let data = Vec::<u8>::from_hex(LEGACY).unwrap();
+        assert_eq!(Attachment::from_strict_serialized::<0xFFFF>(Confined::from_checked(data)).unwrap(), legacy);
+        let data = legacy.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(data.to_hex(), LEGACY);
+
+        let v1 = AttachmentV1::from(legacy);
+        let data = v1.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(AttachmentV1::from_strict_serialized::<0xFFFF>(data).unwrap(), v1);
+    }
+}
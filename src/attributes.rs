@@ -0,0 +1,528 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+use amplify::confinement::{TinyString, TinyVec};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use crate::{TokenNo, LIB_NAME_RGB21};
+
+/// Decimal number represented as `mantissa * 10^-scale`.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct AttrDecimal {
+    pub mantissa: i64,
+    pub scale: u8,
+}
+
+impl AttrDecimal {
+    pub fn new(mantissa: i64, scale: u8) -> Self { AttrDecimal { mantissa, scale } }
+
+    pub fn to_f64(self) -> f64 { self.mantissa as f64 / 10f64.powi(self.scale as i32) }
+
+    /// Removes trailing zeros from the fractional part, so numerically equal decimals have the
+    /// same representation.
+    pub fn normalized(self) -> Self {
+        let mut decimal = self;
+        while decimal.scale > 0 && decimal.mantissa % 10 == 0 {
+            decimal.mantissa /= 10;
+            decimal.scale -= 1;
+        }
+        decimal
+    }
+}
+
+impl Display for AttrDecimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, fract) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{int}.{fract}")
+    }
+}
+
+/// Typed value of an NFT attribute.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21, tags = custom, dumb = Self::Text(strict_dumb!()))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum AttrValue {
+    #[strict_type(tag = 0x00)]
+    Text(TinyString),
+
+    #[strict_type(tag = 0x01)]
+    Integer(i64),
+
+    #[strict_type(tag = 0x02)]
+    Decimal(AttrDecimal),
+
+    /// Date as a UNIX timestamp, in seconds.
+    #[strict_type(tag = 0x03)]
+    Date(i64),
+
+    #[strict_type(tag = 0x04)]
+    Bool(bool),
+}
+
+impl AttrValue {
+    /// Returns the value with decimals normalized with [`AttrDecimal::normalized`].
+    pub fn normalized(&self) -> Self {
+        match self {
+            AttrValue::Decimal(d) => AttrValue::Decimal(d.normalized()),
+            other => other.clone(),
+        }
+    }
+}
+
+impl Display for AttrValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AttrValue::Text(s) => f.write_str(s),
+            AttrValue::Integer(v) | AttrValue::Date(v) => write!(f, "{v}"),
+            AttrValue::Decimal(v) => write!(f, "{v}"),
+            AttrValue::Bool(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// Hint on how the attribute should be displayed, matching the `display_type` of ERC-721
+/// metadata.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[repr(u8)]
+pub enum DisplayHint {
+    /// Display as a property (a label with the value).
+    #[default]
+    #[display("property")]
+    Property = 0,
+
+    /// Display as a numeric ranking (with a progress bar, if a maximum is known).
+    #[display("number")]
+    Number = 1,
+
+    /// Display as a numeric boost.
+    #[display("boost_number")]
+    BoostNumber = 2,
+
+    /// Display as a percentage boost.
+    #[display("boost_percentage")]
+    BoostPercentage = 3,
+
+    /// Display as a calendar date.
+    #[display("date")]
+    Date = 4,
+}
+
+/// Single NFT attribute (also known as a trait), like "Background: Blue".
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct NftAttribute {
+    /// Attribute name (trait type). May be empty for unnamed attributes.
+    pub name: TinyString,
+    pub value: AttrValue,
+    pub display: DisplayHint,
+}
+
+impl NftAttribute {
+    pub fn new(name: &str, value: AttrValue) -> Result<Self, amplify::confinement::Error> {
+        Ok(NftAttribute {
+            name: TinyString::try_from(name.to_owned())?,
+            value,
+            display: DisplayHint::default(),
+        })
+    }
+
+    pub fn with_display(mut self, display: DisplayHint) -> Self {
+        self.display = display;
+        self
+    }
+}
+
+/// List of NFT attributes.
+#[derive(Wrapper, WrapperMut, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct NftAttributes(TinyVec<NftAttribute>);
+
+impl StrictSerialize for NftAttributes {}
+impl StrictDeserialize for NftAttributes {}
+
+impl NftAttributes {
+    pub fn with(attributes: impl IntoIterator<Item = NftAttribute>) -> Result<Self, amplify::confinement::Error> {
+        TinyVec::try_from_iter(attributes).map(Self)
+    }
+
+    /// Returns the first attribute with the given name.
+    pub fn get(&self, name: &str) -> Option<&AttrValue> {
+        self.iter()
+            .find(|attr| attr.name.as_str() == name)
+            .map(|attr| &attr.value)
+    }
+}
+
+/// Rarity statistics over the attributes of a collection.
+///
+/// Rarity score of a token is the sum of `1 / frequency` of each of its attribute values, where the
+/// frequency is the share of the tokens having the same value of the same attribute. Tokens with
+/// the higher score are rarer. Decimal values are compared after normalization, so `10.0` and `10`
+/// are counted as the same value.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RarityReport {
+    /// Total number of tokens analyzed.
+    pub token_count: u32,
+    /// Number of tokens having each attribute value.
+    pub counts: BTreeMap<(TinyString, AttrValue), u32>,
+    /// Rarity score of each token.
+    pub scores: BTreeMap<TokenNo, f64>,
+}
+
+impl RarityReport {
+    /// Computes rarity over the collection attributes.
+    pub fn compute<'a>(tokens: impl IntoIterator<Item = (TokenNo, &'a NftAttributes)>) -> Self {
+        let tokens = tokens.into_iter().collect::<Vec<_>>();
+        let mut counts = BTreeMap::<(TinyString, AttrValue), u32>::new();
+        for (_, attributes) in &tokens {
+            for attr in attributes.iter() {
+                *counts
+                    .entry((attr.name.clone(), attr.value.normalized()))
+                    .or_default() += 1;
+            }
+        }
+        let token_count = tokens.len() as u32;
+        let scores = tokens
+            .iter()
+            .map(|(no, attributes)| {
+                let score = attributes
+                    .iter()
+                    .map(|attr| token_count as f64 / counts[&(attr.name.clone(), attr.value.normalized())] as f64)
+                    .sum();
+                (*no, score)
+            })
+            .collect();
+        RarityReport { token_count, counts, scores }
+    }
+
+    /// Share of tokens having a given attribute value, from 0 to 1.
+    pub fn frequency(&self, name: &str, value: &AttrValue) -> f64 {
+        let Ok(name) = TinyString::try_from(name.to_owned()) else {
+            return 0.0;
+        };
+        if self.token_count == 0 {
+            return 0.0;
+        }
+        self.counts
+            .get(&(name, value.normalized()))
+            .copied()
+            .unwrap_or_default() as f64
+            / self.token_count as f64
+    }
+
+    /// Tokens ordered from the rarest to the most common. Tokens with equal score are ordered by
+    /// their number.
+    pub fn ranking(&self) -> Vec<(TokenNo, f64)> {
+        let mut ranking = self
+            .scores
+            .iter()
+            .map(|(no, score)| (*no, *score))
+            .collect::<Vec<_>>();
+        ranking.sort_by(|(no1, score1), (no2, score2)| score2.total_cmp(score1).then(no1.cmp(no2)));
+        ranking
+    }
+}
+
+#[cfg(feature = "serde")]
+mod _erc721 {
+    use serde_json::{Map, Number, Value};
+
+    use super::*;
+
+    #[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+    #[display(doc_comments)]
+    pub enum AttrJsonError {
+        /// ERC-721 attributes must be a JSON array.
+        NotArray,
+
+        /// attribute #{0} is not a JSON object.
+        NotObject(usize),
+
+        /// attribute #{0} has no value.
+        NoValue(usize),
+
+        /// attribute #{0} has unsupported value type.
+        ValueType(usize),
+
+        /// attribute #{0} has unsupported display type '{1}'.
+        DisplayType(usize, String),
+
+        /// numeric value of attribute #{0} can't be represented exactly.
+        Number(usize),
+
+        /// text of attribute #{0} exceeds 255 bytes.
+        TooLong(usize),
+
+        /// too many attributes; at most 255 attributes are supported.
+        TooMany,
+    }
+
+    impl DisplayHint {
+        pub fn from_erc721(display_type: &str) -> Option<Self> {
+            Some(match display_type {
+                "number" => DisplayHint::Number,
+                "boost_number" => DisplayHint::BoostNumber,
+                "boost_percentage" => DisplayHint::BoostPercentage,
+                "date" => DisplayHint::Date,
+                _ => return None,
+            })
+        }
+
+        pub fn to_erc721(self) -> Option<&'static str> {
+            match self {
+                DisplayHint::Property => None,
+                DisplayHint::Number => Some("number"),
+                DisplayHint::BoostNumber => Some("boost_number"),
+                DisplayHint::BoostPercentage => Some("boost_percentage"),
+                DisplayHint::Date => Some("date"),
+            }
+        }
+    }
+
+    fn decimal_from_json(num: &Number) -> Option<AttrDecimal> {
+        let s = num.to_string();
+        if s.contains(['e', 'E']) {
+            return None;
+        }
+        let (int, fract) = s.split_once('.').unwrap_or((&s, ""));
+        let fract = fract.trim_end_matches('0');
+        let scale = u8::try_from(fract.len()).ok()?;
+        let mantissa = format!("{int}{fract}").parse().ok()?;
+        Some(AttrDecimal { mantissa, scale })
+    }
+
+    /// Exports decimal as a JSON number which imports back to the same normalized decimal. Decimals
+    /// which can't be represented exactly by a JSON number, like the ones with mantissa above
+    /// 2^53, are exported as strings with their exact value.
+    fn decimal_to_json(decimal: AttrDecimal) -> Value {
+        let decimal = decimal.normalized();
+        let exact = decimal.to_string();
+        exact
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .filter(|num| decimal_from_json(num) == Some(decimal))
+            .map(Value::Number)
+            .unwrap_or(Value::String(exact))
+    }
+
+    impl AttrValue {
+        fn from_json(value: &Value, display: DisplayHint, index: usize) -> Result<Self, AttrJsonError> {
+            Ok(match value {
+                Value::String(s) => {
+                    AttrValue::Text(TinyString::try_from(s.clone()).map_err(|_| AttrJsonError::TooLong(index))?)
+                }
+                Value::Bool(b) => AttrValue::Bool(*b),
+                Value::Number(n) if display == DisplayHint::Date => {
+                    AttrValue::Date(n.as_i64().ok_or(AttrJsonError::Number(index))?)
+                }
+                Value::Number(n) => match n.as_i64() {
+                    Some(v) => AttrValue::Integer(v),
+                    None => AttrValue::Decimal(decimal_from_json(n).ok_or(AttrJsonError::Number(index))?),
+                },
+                Value::Null => return Err(AttrJsonError::NoValue(index)),
+                Value::Array(_) | Value::Object(_) => return Err(AttrJsonError::ValueType(index)),
+            })
+        }
+
+        fn to_json(&self) -> Value {
+            match self {
+                AttrValue::Text(s) => Value::String(s.to_string()),
+                AttrValue::Integer(v) | AttrValue::Date(v) => Value::Number((*v).into()),
+                AttrValue::Decimal(d) => decimal_to_json(*d),
+                AttrValue::Bool(b) => Value::Bool(*b),
+            }
+        }
+    }
+
+    impl NftAttributes {
+        /// Parses the `attributes` array of ERC-721 (OpenSea) metadata JSON.
+        pub fn from_erc721_json(json: &Value) -> Result<Self, AttrJsonError> {
+            let list = json.as_array().ok_or(AttrJsonError::NotArray)?;
+            let mut attributes = Vec::with_capacity(list.len());
+            for (index, item) in list.iter().enumerate() {
+                let obj = item.as_object().ok_or(AttrJsonError::NotObject(index))?;
+                let display = match obj.get("display_type") {
+                    None | Some(Value::Null) => DisplayHint::Property,
+                    Some(Value::String(s)) => {
+                        DisplayHint::from_erc721(s).ok_or_else(|| AttrJsonError::DisplayType(index, s.clone()))?
+                    }
+                    Some(other) => return Err(AttrJsonError::DisplayType(index, other.to_string())),
+                };
+                let name = match obj.get("trait_type") {
+                    None | Some(Value::Null) => s!(""),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                };
+                let name = TinyString::try_from(name).map_err(|_| AttrJsonError::TooLong(index))?;
+                let value = obj.get("value").ok_or(AttrJsonError::NoValue(index))?;
+                let value = AttrValue::from_json(value, display, index)?;
+                attributes.push(NftAttribute { name, value, display });
+            }
+            NftAttributes::with(attributes).map_err(|_| AttrJsonError::TooMany)
+        }
+
+        /// Produces the `attributes` array of ERC-721 (OpenSea) metadata JSON.
+        pub fn to_erc721_json(&self) -> Value {
+            Value::Array(
+                self.iter()
+                    .map(|attr| {
+                        let mut obj = Map::new();
+                        if let Some(display) = attr.display.to_erc721() {
+                            obj.insert(s!("display_type"), Value::String(display.to_owned()));
+                        }
+                        if !attr.name.is_empty() {
+                            obj.insert(s!("trait_type"), Value::String(attr.name.to_string()));
+                        }
+                        obj.insert(s!("value"), attr.value.to_json());
+                        Value::Object(obj)
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+#[cfg(feature = "serde")]
+pub use _erc721::AttrJsonError;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(s: &str) -> AttrValue { AttrValue::Text(TinyString::try_from(s.to_owned()).unwrap()) }
+
+    fn attrs(list: &[(&str, AttrValue)]) -> NftAttributes {
+        NftAttributes::with(
+            list.iter()
+                .map(|(name, value)| NftAttribute::new(name, value.clone()).unwrap()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn decimal_display() {
+        assert_eq!(AttrDecimal::new(140, 2).to_string(), "1.40");
+        assert_eq!(AttrDecimal::new(-5, 3).to_string(), "-0.005");
+        assert_eq!(AttrDecimal::new(42, 0).to_string(), "42");
+        assert_eq!(AttrDecimal::new(1400, 3).normalized(), AttrDecimal::new(14, 1));
+        assert_eq!(AttrDecimal::new(-500, 2).normalized(), AttrDecimal::new(-5, 0));
+        assert_eq!(AttrDecimal::new(0, 2).normalized(), AttrDecimal::new(0, 0));
+    }
+
+    #[test]
+    fn rarity() {
+        let a = attrs(&[("Background", text("Blue")), ("Eyes", text("Laser"))]);
+        let b = attrs(&[("Background", text("Blue")), ("Eyes", text("Normal"))]);
+        let c = attrs(&[("Background", text("Gold")), ("Eyes", text("Normal"))]);
+        let d = attrs(&[("Background", text("Blue")), ("Eyes", text("Normal"))]);
+        let report = RarityReport::compute([(0.into(), &a), (1.into(), &b), (2.into(), &c), (3.into(), &d)]);
+
+        assert_eq!(report.token_count, 4);
+        assert_eq!(report.frequency("Background", &text("Blue")), 0.75);
+        assert_eq!(report.frequency("Eyes", &text("Laser")), 0.25);
+        assert_eq!(report.frequency("Eyes", &text("Wink")), 0.0);
+
+        let ranking = report.ranking();
+        assert_eq!(ranking[0].0, TokenNo::from(0));
+        assert_eq!(ranking[1].0, TokenNo::from(2));
+        assert_eq!(ranking[2].0, TokenNo::from(1));
+        assert_eq!(ranking[3].0, TokenNo::from(3));
+        assert_eq!(ranking[2].1, ranking[3].1);
+    }
+
+    #[test]
+    fn strict_encoding() {
+        let list = attrs(&[
+            ("Background", text("Blue")),
+            ("Rarity", AttrValue::Integer(3)),
+            ("Weight", AttrValue::Decimal(AttrDecimal::new(145, 1))),
+            ("Born", AttrValue::Date(1_700_000_000)),
+            ("Shiny", AttrValue::Bool(true)),
+        ]);
+        let data = list.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(NftAttributes::from_strict_serialized::<0xFFFF>(data).unwrap(), list);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn erc721_json() {
+        let json = serde_json::json!([
+            { "trait_type": "Background", "value": "Blue" },
+            { "display_type": "number", "trait_type": "Generation", "value": 2 },
+            { "display_type": "boost_percentage", "trait_type": "Stamina", "value": 10.5 },
+            { "display_type": "date", "trait_type": "Birthday", "value": 1546360800 },
+            { "trait_type": "Shiny", "value": false },
+            { "value": "Happy" }
+        ]);
+        let list = NftAttributes::from_erc721_json(&json).unwrap();
+        assert_eq!(list.get("Background"), Some(&text("Blue")));
+        assert_eq!(list[1].display, DisplayHint::Number);
+        assert_eq!(list.get("Stamina"), Some(&AttrValue::Decimal(AttrDecimal::new(105, 1))));
+        assert_eq!(list.get("Birthday"), Some(&AttrValue::Date(1546360800)));
+        assert_eq!(list.get("Shiny"), Some(&AttrValue::Bool(false)));
+        assert_eq!(list.get(""), Some(&text("Happy")));
+        assert_eq!(list.to_erc721_json(), json);
+
+        let list = attrs(&[
+            ("Weight", AttrValue::Decimal(AttrDecimal::new(100, 1))),
+            ("Height", AttrValue::Decimal(AttrDecimal::new(1_234_567_890_123_456_780, 10))),
+        ]);
+        let json = list.to_erc721_json();
+        assert_eq!(json[0]["value"], serde_json::json!(10.0));
+        assert_eq!(json[1]["value"], serde_json::json!("123456789.012345678"));
+        let imported = NftAttributes::from_erc721_json(&json).unwrap();
+        assert_eq!(imported.get("Weight"), Some(&AttrValue::Decimal(AttrDecimal::new(10, 0))));
+        let report = RarityReport::compute([(0.into(), &list), (1.into(), &imported)]);
+        assert_eq!(report.frequency("Weight", &AttrValue::Decimal(AttrDecimal::new(100, 1))), 1.0);
+
+        assert_eq!(NftAttributes::from_erc721_json(&serde_json::json!({})), Err(AttrJsonError::NotArray));
+        assert_eq!(
+            NftAttributes::from_erc721_json(&serde_json::json!([{ "trait_type": "X" }])),
+            Err(AttrJsonError::NoValue(0))
+        );
+        assert_eq!(
+            NftAttributes::from_erc721_json(&serde_json::json!([{ "display_type": "ranking", "value": 1 }])),
+            Err(AttrJsonError::DisplayType(0, s!("ranking")))
+        );
+    }
+}
@@ -0,0 +1,373 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::confinement::{SmallBlob, SmallVec};
+use amplify::Bytes32;
+use commit_verify::{DigestExt, Sha256};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use crate::{Attachment, DigestAlgo, MediaType, LIB_NAME_RGB21};
+
+/// Maximal size of a single chunk, matching the [`SmallBlob`] size limit.
+pub const MEDIA_CHUNK_MAX_SIZE: u16 = u16::MAX;
+
+/// Tag for hashing Merkle tree leaves of the chunked media.
+pub const MEDIA_CHUNK_LEAF_TAG: &str = "urn:lnp-bp:rgb21:media-chunk:leaf#2026-10-18";
+/// Tag for hashing Merkle tree branches of the chunked media.
+pub const MEDIA_CHUNK_NODE_TAG: &str = "urn:lnp-bp:rgb21:media-chunk:node#2026-10-18";
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum ChunkError {
+    /// chunk size must be non-zero.
+    ZeroChunkSize,
+
+    /// media of {0} bytes requires more chunks than can be listed in a manifest.
+    TooLarge(u64),
+
+    /// manifest lists {expected} chunks, while {actual} chunks were provided.
+    ChunkCount { expected: usize, actual: usize },
+
+    /// manifest declares {len} bytes split into {chunk_size}-byte chunks, which doesn't match
+    /// {count} chunks listed in it.
+    InconsistentLength { len: u64, chunk_size: u16, count: usize },
+
+    /// chunk #{index} has {actual} bytes, while {expected} bytes are expected.
+    ChunkLength { index: u32, expected: usize, actual: usize },
+
+    /// chunk #{0} doesn't match its digest in the manifest.
+    ChunkDigest(u32),
+
+    /// Merkle root in the manifest doesn't commit to the listed chunk digests.
+    MerkleRoot,
+
+    /// chunk #{0} is absent in the manifest.
+    NoChunk(u32),
+}
+
+/// Manifest describing media split into multiple chunks, each of which fits the embedded media
+/// size limit.
+///
+/// Chunks are referenced by their SHA-256 digests (the same as used by [`Attachment`]). The
+/// manifest additionally commits to a Merkle root over the chunk digests, allowing a single chunk
+/// to be verified with a [`ChunkProof`] without the knowledge of other chunk digests.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct ChunkedMedia {
+    #[cfg_attr(feature = "serde", serde(with = "serde_with::rust::display_fromstr"))]
+    pub mime: MediaType,
+    /// Total length of the media, in bytes.
+    pub len: u64,
+    /// Size of each chunk except the last one, which may be shorter.
+    pub chunk_size: u16,
+    /// Ordered list of SHA-256 chunk digests.
+    pub chunks: SmallVec<Bytes32>,
+    pub merkle_root: Bytes32,
+}
+impl StrictSerialize for ChunkedMedia {}
+impl StrictDeserialize for ChunkedMedia {}
+
+/// Merkle path proving inclusion of a single chunk into [`ChunkedMedia`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct ChunkProof {
+    pub index: u32,
+    /// Sibling hashes from the leaf level up to the root. Levels where the node has no sibling are
+    /// skipped.
+    pub path: SmallVec<Bytes32>,
+}
+impl StrictSerialize for ChunkProof {}
+impl StrictDeserialize for ChunkProof {}
+
+impl ChunkedMedia {
+    /// Splits media into chunks of the maximal size.
+    pub fn split(mime: MediaType, data: impl AsRef<[u8]>) -> Result<(Self, Vec<SmallBlob>), ChunkError> {
+        Self::split_with(mime, data, MEDIA_CHUNK_MAX_SIZE)
+    }
+
+    /// Splits media into chunks of a given size, returning the manifest and the chunks.
+    pub fn split_with(
+        mime: MediaType,
+        data: impl AsRef<[u8]>,
+        chunk_size: u16,
+    ) -> Result<(Self, Vec<SmallBlob>), ChunkError> {
+        let data = data.as_ref();
+        if chunk_size == 0 {
+            return Err(ChunkError::ZeroChunkSize);
+        }
+        let chunks = data
+            .chunks(chunk_size as usize)
+            .map(|chunk| SmallBlob::from_checked(chunk.to_vec()))
+            .collect::<Vec<_>>();
+        let digests = SmallVec::try_from_iter(chunks.iter().map(|chunk| DigestAlgo::Sha256.digest(chunk)))
+            .map_err(|_| ChunkError::TooLarge(data.len() as u64))?;
+        let merkle_root = merkle_root(&digests);
+        let manifest = ChunkedMedia {
+            mime,
+            len: data.len() as u64,
+            chunk_size,
+            chunks: digests,
+            merkle_root,
+        };
+        Ok((manifest, chunks))
+    }
+
+    /// Number of chunks implied by the media length and chunk size.
+    pub fn expected_chunk_count(&self) -> Result<usize, ChunkError> {
+        if self.chunk_size == 0 {
+            return Err(ChunkError::ZeroChunkSize);
+        }
+        Ok(self.len.div_ceil(self.chunk_size as u64) as usize)
+    }
+
+    /// Expected length of a chunk with the given index.
+    pub fn chunk_len(&self, index: u32) -> Result<usize, ChunkError> {
+        let count = self.expected_chunk_count()?;
+        if index as usize >= count {
+            return Err(ChunkError::NoChunk(index));
+        }
+        let offset = index as u64 * self.chunk_size as u64;
+        Ok((self.len - offset).min(self.chunk_size as u64) as usize)
+    }
+
+    /// Attachment referencing a single chunk.
+    pub fn chunk_attachment(&self, index: u32) -> Option<Attachment> {
+        self.chunks.get(index as usize).map(|digest| Attachment {
+            mime: MediaType::with("application/octet-stream"),
+            digest: *digest,
+        })
+    }
+
+    /// Checks internal consistency of the manifest: chunk count matching the length and the Merkle
+    /// root committing to the chunk digests.
+    pub fn validate(&self) -> Result<(), ChunkError> {
+        let count = self.expected_chunk_count()?;
+        if count != self.chunks.len() {
+            return Err(ChunkError::InconsistentLength {
+                len: self.len,
+                chunk_size: self.chunk_size,
+                count: self.chunks.len(),
+            });
+        }
+        if merkle_root(&self.chunks) != self.merkle_root {
+            return Err(ChunkError::MerkleRoot);
+        }
+        Ok(())
+    }
+
+    /// Verifies a single chunk against its digest in the manifest.
+    pub fn verify_chunk(&self, index: u32, chunk: impl AsRef<[u8]>) -> Result<(), ChunkError> {
+        let chunk = chunk.as_ref();
+        let expected = self.chunk_len(index)?;
+        if chunk.len() != expected {
+            return Err(ChunkError::ChunkLength { index, expected, actual: chunk.len() });
+        }
+        let digest = self
+            .chunks
+            .get(index as usize)
+            .ok_or(ChunkError::NoChunk(index))?;
+        if DigestAlgo::Sha256.digest(chunk) != *digest {
+            return Err(ChunkError::ChunkDigest(index));
+        }
+        Ok(())
+    }
+
+    /// Reassembles the media from the chunks, verifying integrity of each of them.
+    ///
+    /// The declared media length is not trusted for memory allocation: the output grows only as
+    /// verified chunks arrive.
+    pub fn reassemble<C: AsRef<[u8]>>(&self, chunks: impl IntoIterator<Item = C>) -> Result<Vec<u8>, ChunkError> {
+        self.validate()?;
+        let mut data = Vec::new();
+        let mut count = 0usize;
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            if index >= self.chunks.len() {
+                return Err(ChunkError::ChunkCount { expected: self.chunks.len(), actual: index + 1 });
+            }
+            self.verify_chunk(index as u32, &chunk)?;
+            data.extend_from_slice(chunk.as_ref());
+            count += 1;
+        }
+        if count != self.chunks.len() {
+            return Err(ChunkError::ChunkCount { expected: self.chunks.len(), actual: count });
+        }
+        Ok(data)
+    }
+
+    /// Constructs Merkle inclusion proof for a chunk.
+    pub fn proof(&self, index: u32) -> Result<ChunkProof, ChunkError> {
+        if index as usize >= self.chunks.len() {
+            return Err(ChunkError::NoChunk(index));
+        }
+        let mut level = leaves(&self.chunks);
+        let mut pos = index as usize;
+        let mut path = vec![];
+        while level.len() > 1 {
+            let sibling = pos ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                path.push(*hash);
+            }
+            level = next_level(&level);
+            pos /= 2;
+        }
+        Ok(ChunkProof { index, path: SmallVec::from_checked(path) })
+    }
+}
+
+impl ChunkProof {
+    /// Verifies that the chunk data are committed under the Merkle root of a manifest with the
+    /// given number of chunks.
+    pub fn verify(&self, merkle_root: Bytes32, chunk_count: usize, chunk: impl AsRef<[u8]>) -> bool {
+        if self.index as usize >= chunk_count {
+            return false;
+        }
+        let mut hash = leaf_hash(self.index, DigestAlgo::Sha256.digest(chunk));
+        let mut pos = self.index as usize;
+        let mut width = chunk_count;
+        let mut path = self.path.iter();
+        while width > 1 {
+            let sibling = pos ^ 1;
+            if sibling < width {
+                let Some(other) = path.next() else {
+                    return false;
+                };
+                hash = if pos % 2 == 0 { node_hash(hash, *other) } else { node_hash(*other, hash) };
+            }
+            pos /= 2;
+            width = width.div_ceil(2);
+        }
+        path.next().is_none() && hash == merkle_root
+    }
+}
+
+fn leaf_hash(index: u32, digest: Bytes32) -> Bytes32 {
+    let mut engine = Sha256::from_tag(MEDIA_CHUNK_LEAF_TAG);
+    engine.input_raw(&index.to_le_bytes());
+    engine.input_raw(digest.as_slice());
+    Bytes32::from_byte_array(engine.finish())
+}
+
+fn node_hash(left: Bytes32, right: Bytes32) -> Bytes32 {
+    let mut engine = Sha256::from_tag(MEDIA_CHUNK_NODE_TAG);
+    engine.input_raw(left.as_slice());
+    engine.input_raw(right.as_slice());
+    Bytes32::from_byte_array(engine.finish())
+}
+
+fn leaves(digests: &[Bytes32]) -> Vec<Bytes32> {
+    digests
+        .iter()
+        .enumerate()
+        .map(|(index, digest)| leaf_hash(index as u32, *digest))
+        .collect()
+}
+
+// A node without a sibling is moved to the next level unchanged.
+fn next_level(level: &[Bytes32]) -> Vec<Bytes32> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(*left, *right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+fn merkle_root(digests: &[Bytes32]) -> Bytes32 {
+    let mut level = leaves(digests);
+    if level.is_empty() {
+        return Bytes32::from_byte_array(Sha256::from_tag(MEDIA_CHUNK_NODE_TAG).finish());
+    }
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn media(len: usize) -> Vec<u8> { (0..len).map(|i| (i * 7 % 251) as u8).collect() }
+
+    #[test]
+    fn split_reassemble() {
+        let data = media(200_000);
+        let (manifest, chunks) = ChunkedMedia::split(MediaType::with("image/png"), &data).unwrap();
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(manifest.len, 200_000);
+        assert_eq!(manifest.validate(), Ok(()));
+        assert_eq!(manifest.reassemble(&chunks).unwrap(), data);
+
+        let data = manifest.to_strict_serialized::<0xFFFFFF>().unwrap();
+        assert_eq!(ChunkedMedia::from_strict_serialized::<0xFFFFFF>(data).unwrap(), manifest);
+    }
+
+    #[test]
+    fn empty_media() {
+        let (manifest, chunks) = ChunkedMedia::split(MediaType::with("text/plain"), []).unwrap();
+        assert!(chunks.is_empty());
+        assert_eq!(manifest.reassemble(Vec::<Vec<u8>>::new()).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn integrity() {
+        let data = media(1000);
+        let (manifest, mut chunks) = ChunkedMedia::split_with(MediaType::with("image/png"), &data, 300).unwrap();
+        assert_eq!(manifest.chunk_len(3), Ok(100));
+
+        assert_eq!(manifest.reassemble(&chunks[..3]), Err(ChunkError::ChunkCount { expected: 4, actual: 3 }));
+        chunks.swap(0, 1);
+        assert_eq!(manifest.reassemble(&chunks), Err(ChunkError::ChunkDigest(0)));
+        chunks.swap(0, 1);
+        chunks[3] = SmallBlob::from_checked(vec![0u8; 99]);
+        assert_eq!(manifest.reassemble(&chunks), Err(ChunkError::ChunkLength { index: 3, expected: 100, actual: 99 }));
+
+        let mut forged = manifest.clone();
+        forged.chunks = SmallVec::from_checked(manifest.chunks.iter().rev().copied().collect());
+        assert_eq!(forged.validate(), Err(ChunkError::MerkleRoot));
+        forged.len = 10_000;
+        assert!(matches!(forged.validate(), Err(ChunkError::InconsistentLength { .. })));
+    }
+
+    #[test]
+    fn merkle_proofs() {
+        for count in [1usize, 2, 3, 5, 8, 13] {
+            let data = media(count * 10);
+            let (manifest, chunks) = ChunkedMedia::split_with(MediaType::with("image/png"), &data, 10).unwrap();
+            assert_eq!(chunks.len(), count);
+            for (index, chunk) in chunks.iter().enumerate() {
+                let proof = manifest.proof(index as u32).unwrap();
+                assert!(proof.verify(manifest.merkle_root, count, chunk));
+                assert!(!proof.verify(manifest.merkle_root, count, b"forged"));
+                if count > 1 {
+                    let other = chunks[(index + 1) % count].as_slice();
+                    assert!(!proof.verify(manifest.merkle_root, count, other));
+                }
+            }
+            assert_eq!(manifest.proof(count as u32), Err(ChunkError::NoChunk(count as u32)));
+        }
+    }
+}
@@ -0,0 +1,551 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! IPFS content identifiers (CIDs) for attachments.
+//!
+//! An [`Attachment`] commits to the SHA-256 digest of the raw media bytes. The same digest is used
+//! by IPFS for CIDv1 identifiers with the `raw` codec (`bafkrei...` strings), so the conversion
+//! between the two is possible offline and without any access to the data.
+//!
+//! # When a CID can't be derived from a digest
+//!
+//! By default `ipfs add` splits files into UnixFS chunks and links them with a `dag-pb` node. The
+//! hash inside such CID (all CIDv0 `Qm...` strings and CIDv1 `bafybei...` strings) commits to the
+//! encoded DAG structure, which depends on the chunker, the DAG layout and the IPFS implementation
+//! version. It is not a digest of the file content, and thus can't be computed from an attachment
+//! digest (nor an attachment digest be computed from it). Only files added as a single raw block
+//! (for instance with `ipfs add --cid-version=1 --raw-leaves` for files not exceeding the chunk
+//! size, or with `ipfs block put`) have CIDs representable by this module.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::Bytes32;
+
+use crate::{Attachment, AttachmentV1, DigestAlgo, MediaType};
+
+/// Multicodec code for raw binary data.
+pub const MULTICODEC_RAW: u64 = 0x55;
+/// Multicodec code for MerkleDAG protobuf nodes, used by UnixFS.
+pub const MULTICODEC_DAG_PB: u64 = 0x70;
+/// Multihash code for SHA2-256.
+pub const MULTIHASH_SHA2_256: u64 = 0x12;
+/// Multihash code for BLAKE3.
+pub const MULTIHASH_BLAKE3: u64 = 0x1e;
+
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE16_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum CidError {
+    /// empty CID string.
+    Empty,
+
+    /// unsupported multibase prefix '{0}'.
+    UnknownMultibase(char),
+
+    /// invalid {base} character '{ch}' at position {pos} of the CID string.
+    InvalidChar { base: Multibase, ch: char, pos: usize },
+
+    /// non-canonical {0} encoding of the CID string.
+    NonCanonical(Multibase),
+
+    /// invalid or truncated unsigned varint at byte {0} of the CID.
+    InvalidVarint(usize),
+
+    /// unsupported CID version {0}.
+    UnsupportedVersion(u64),
+
+    /// multihash declares {declared}-byte digest, while {actual} bytes are present.
+    MultihashLength { declared: u64, actual: usize },
+
+    /// multihash function {0:#x} is not supported; only SHA2-256 and BLAKE3 digests can be
+    /// mapped to attachments.
+    UnsupportedHash(u64),
+
+    /// digest algorithm {0} has no multihash representation.
+    UnrepresentableAlgo(DigestAlgo),
+
+    /// CID uses dag-pb codec, meaning that it identifies a UnixFS DAG of (possibly chunked) file
+    /// rather than the file content; such CID can't be mapped to an attachment digest.
+    DagPb,
+
+    /// CID uses unsupported multicodec {0:#x}; only raw codec CIDs can be mapped to attachments.
+    UnsupportedCodec(u64),
+
+    /// {0}-byte digest is not supported; attachments use 32-byte digests.
+    DigestLength(usize),
+}
+
+/// Multibase encodings supported for CID strings.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+pub enum Multibase {
+    /// RFC 4648 lowercase base32 without padding (prefix `b`); the canonical CIDv1 encoding.
+    #[default]
+    #[display("base32")]
+    Base32Lower,
+
+    /// Bitcoin base58 (prefix `z`; CIDv0 strings use it without a prefix).
+    #[display("base58btc")]
+    Base58Btc,
+
+    /// Lowercase hexadecimal (prefix `f`).
+    #[display("base16")]
+    Base16Lower,
+}
+
+impl Multibase {
+    pub const fn prefix(self) -> char {
+        match self {
+            Multibase::Base32Lower => 'b',
+            Multibase::Base58Btc => 'z',
+            Multibase::Base16Lower => 'f',
+        }
+    }
+
+    pub fn from_prefix(prefix: char) -> Result<Self, CidError> {
+        Ok(match prefix {
+            'b' => Multibase::Base32Lower,
+            'z' => Multibase::Base58Btc,
+            'f' => Multibase::Base16Lower,
+            other => return Err(CidError::UnknownMultibase(other)),
+        })
+    }
+
+    /// Encodes data without the multibase prefix.
+    pub fn encode(self, data: &[u8]) -> String {
+        match self {
+            Multibase::Base32Lower => base32_encode(data),
+            Multibase::Base58Btc => base58_encode(data),
+            Multibase::Base16Lower => data
+                .iter()
+                .flat_map(|b| [BASE16_ALPHABET[(b >> 4) as usize], BASE16_ALPHABET[(b & 0x0F) as usize]])
+                .map(char::from)
+                .collect(),
+        }
+    }
+
+    /// Decodes data provided without the multibase prefix. The `offset` is used to report
+    /// positions of invalid characters relative to the full string.
+    pub fn decode(self, s: &str, offset: usize) -> Result<Vec<u8>, CidError> {
+        let invalid = |pos: usize, ch: char| CidError::InvalidChar { base: self, ch, pos: pos + offset };
+        let data = match self {
+            Multibase::Base32Lower => base32_decode(s).map_err(|(pos, ch)| invalid(pos, ch)),
+            Multibase::Base58Btc => base58_decode(s).map_err(|(pos, ch)| invalid(pos, ch)),
+            Multibase::Base16Lower => {
+                if s.len() % 2 != 0 {
+                    return Err(CidError::NonCanonical(self));
+                }
+                let nibble = |pos: usize, ch: char| {
+                    BASE16_ALPHABET
+                        .iter()
+                        .position(|c| *c as char == ch)
+                        .map(|v| v as u8)
+                        .ok_or_else(|| invalid(pos, ch))
+                };
+                let chars = s.chars().collect::<Vec<_>>();
+                chars
+                    .chunks(2)
+                    .enumerate()
+                    .map(|(i, pair)| Ok((nibble(i * 2, pair[0])? << 4) | nibble(i * 2 + 1, pair[1])?))
+                    .collect::<Result<_, _>>()
+            }
+        }?;
+        if self.encode(&data) != s {
+            return Err(CidError::NonCanonical(self));
+        }
+        Ok(data)
+    }
+}
+
+/// Version of the CID format.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+pub enum CidVersion {
+    /// Legacy base58 SHA2-256 dag-pb identifiers (`Qm...`).
+    #[display("CIDv0")]
+    V0,
+    #[display("CIDv1")]
+    V1,
+}
+
+/// IPFS content identifier.
+///
+/// The type is able to parse any CIDv0 or CIDv1 string using one of the supported multibase
+/// encodings, but only raw codec CIDs with SHA2-256 or BLAKE3 multihash can be converted into
+/// attachments. See the [module-level documentation](self) for the details.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Cid {
+    version: CidVersion,
+    codec: u64,
+    hash_fn: u64,
+    digest: Vec<u8>,
+    base: Multibase,
+}
+
+impl Cid {
+    /// Constructs CIDv1 with the raw codec for a 32-byte digest.
+    pub fn raw(hash_fn: u64, digest: Bytes32) -> Self {
+        Cid {
+            version: CidVersion::V1,
+            codec: MULTICODEC_RAW,
+            hash_fn,
+            digest: digest.to_byte_array().to_vec(),
+            base: Multibase::Base32Lower,
+        }
+    }
+
+    pub fn version(&self) -> CidVersion { self.version }
+    pub fn codec(&self) -> u64 { self.codec }
+    pub fn hash_fn(&self) -> u64 { self.hash_fn }
+    pub fn digest(&self) -> &[u8] { &self.digest }
+    pub fn multibase(&self) -> Multibase { self.base }
+
+    /// Returns a copy of the CID which will be displayed using a given multibase encoding. Has no
+    /// effect on CIDv0, which is always displayed in base58 without a prefix.
+    pub fn with_multibase(mut self, base: Multibase) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Binary representation of the CID.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.digest.len() + 8);
+        if self.version == CidVersion::V1 {
+            write_varint(&mut bytes, 1);
+            write_varint(&mut bytes, self.codec);
+        }
+        write_varint(&mut bytes, self.hash_fn);
+        write_varint(&mut bytes, self.digest.len() as u64);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+
+    /// Parses binary representation of the CID.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CidError> {
+        let mut pos = 0usize;
+        let (version, codec) = if bytes.len() == 34 && bytes[0] == MULTIHASH_SHA2_256 as u8 && bytes[1] == 0x20 {
+            (CidVersion::V0, MULTICODEC_DAG_PB)
+        } else {
+            match read_varint(bytes, &mut pos)? {
+                1 => (CidVersion::V1, read_varint(bytes, &mut pos)?),
+                other => return Err(CidError::UnsupportedVersion(other)),
+            }
+        };
+        let hash_fn = read_varint(bytes, &mut pos)?;
+        let len = read_varint(bytes, &mut pos)?;
+        let digest = bytes[pos..].to_vec();
+        if len != digest.len() as u64 {
+            return Err(CidError::MultihashLength { declared: len, actual: digest.len() });
+        }
+        let base = if version == CidVersion::V0 { Multibase::Base58Btc } else { Multibase::Base32Lower };
+        Ok(Cid { version, codec, hash_fn, digest, base })
+    }
+
+    /// Checks that the CID identifies raw content and returns its digest algorithm and value.
+    pub fn raw_digest(&self) -> Result<(DigestAlgo, Bytes32), CidError> {
+        match self.codec {
+            MULTICODEC_RAW => {}
+            MULTICODEC_DAG_PB => return Err(CidError::DagPb),
+            other => return Err(CidError::UnsupportedCodec(other)),
+        }
+        let algo = match self.hash_fn {
+            MULTIHASH_SHA2_256 => DigestAlgo::Sha256,
+            MULTIHASH_BLAKE3 => DigestAlgo::Blake3,
+            other => return Err(CidError::UnsupportedHash(other)),
+        };
+        let digest = Bytes32::copy_from_slice(&self.digest).map_err(|_| CidError::DigestLength(self.digest.len()))?;
+        Ok((algo, digest))
+    }
+}
+
+impl Display for Cid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let bytes = self.to_bytes();
+        match self.version {
+            CidVersion::V0 => f.write_str(&base58_encode(&bytes)),
+            CidVersion::V1 => write!(f, "{}{}", self.base.prefix(), self.base.encode(&bytes)),
+        }
+    }
+}
+
+impl FromStr for Cid {
+    type Err = CidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 46 && s.starts_with("Qm") {
+            let bytes = Multibase::Base58Btc.decode(s, 0)?;
+            return Cid::from_bytes(&bytes);
+        }
+        let prefix = s.chars().next().ok_or(CidError::Empty)?;
+        let base = Multibase::from_prefix(prefix)?;
+        let bytes = base.decode(&s[1..], 1)?;
+        let mut cid = Cid::from_bytes(&bytes)?;
+        if cid.version == CidVersion::V0 {
+            // CIDv0 must not be multibase-prefixed
+            return Err(CidError::UnsupportedVersion(0));
+        }
+        cid.base = base;
+        Ok(cid)
+    }
+}
+
+impl Attachment {
+    /// Computes CIDv1 of the attachment content, assuming it is stored in IPFS as a single raw
+    /// block.
+    pub fn to_cid(&self) -> Cid { Cid::raw(MULTIHASH_SHA2_256, self.digest) }
+
+    /// Constructs attachment from a raw codec SHA2-256 CID. Since CID doesn't carry media type
+    /// information, it must be provided by the caller.
+    pub fn from_cid(cid: &Cid, mime: MediaType) -> Result<Self, CidError> {
+        match cid.raw_digest()? {
+            (DigestAlgo::Sha256, digest) => Ok(Attachment { mime, digest }),
+            _ => Err(CidError::UnsupportedHash(cid.hash_fn)),
+        }
+    }
+}
+
+impl AttachmentV1 {
+    /// Computes CIDv1 of the attachment content, assuming it is stored in IPFS as a single raw
+    /// block.
+    ///
+    /// # Errors
+    ///
+    /// If the attachment uses tagged hash, which has no multihash representation.
+    pub fn to_cid(&self) -> Result<Cid, CidError> {
+        let hash_fn = match self.algo {
+            DigestAlgo::Sha256 => MULTIHASH_SHA2_256,
+            DigestAlgo::Blake3 => MULTIHASH_BLAKE3,
+            DigestAlgo::TaggedSha256 => return Err(CidError::UnrepresentableAlgo(self.algo)),
+        };
+        Ok(Cid::raw(hash_fn, self.digest))
+    }
+
+    /// Constructs attachment from a raw codec SHA2-256 or BLAKE3 CID. Since CID doesn't carry
+    /// media type information, it must be provided by the caller.
+    pub fn from_cid(cid: &Cid, mime: MediaType) -> Result<Self, CidError> {
+        let (algo, digest) = cid.raw_digest()?;
+        Ok(AttachmentV1 { mime, algo, digest })
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, CidError> {
+    let start = *pos;
+    let mut value = 0u64;
+    // multiformats limit varints to 9 bytes
+    for shift in (0..63).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or(CidError::InvalidVarint(start))?;
+        *pos += 1;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            // reject non-minimal encodings
+            if byte == 0 && *pos - start > 1 {
+                return Err(CidError::InvalidVarint(start));
+            }
+            return Ok(value);
+        }
+    }
+    Err(CidError::InvalidVarint(start))
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut s = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut acc = 0u16;
+    let mut bits = 0u8;
+    for byte in data {
+        acc = (acc << 8) | *byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            s.push(BASE32_ALPHABET[((acc >> bits) & 0x1F) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        s.push(BASE32_ALPHABET[((acc << (5 - bits)) & 0x1F) as usize] as char);
+    }
+    s
+}
+
+fn base32_decode(s: &str) -> Result<Vec<u8>, (usize, char)> {
+    let mut data = Vec::with_capacity(s.len() * 5 / 8);
+    let mut acc = 0u16;
+    let mut bits = 0u8;
+    for (pos, ch) in s.chars().enumerate() {
+        let val = BASE32_ALPHABET
+            .iter()
+            .position(|c| *c as char == ch)
+            .ok_or((pos, ch))?;
+        acc = (acc << 5) | val as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((acc >> bits) as u8);
+        }
+    }
+    Ok(data)
+}
+
+fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+    let mut digits = Vec::<u8>::with_capacity(data.len() * 138 / 100 + 1);
+    for byte in &data[zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut s = String::with_capacity(zeros + digits.len());
+    s.push_str(&"1".repeat(zeros));
+    s.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|d| BASE58_ALPHABET[*d as usize] as char),
+    );
+    s
+}
+
+fn base58_decode(s: &str) -> Result<Vec<u8>, (usize, char)> {
+    let zeros = s.chars().take_while(|c| *c == '1').count();
+    let mut bytes = Vec::<u8>::with_capacity(s.len() * 733 / 1000 + 1);
+    for (pos, ch) in s.chars().enumerate().skip(zeros) {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|c| *c as char == ch)
+            .ok_or((pos, ch))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut data = vec![0u8; zeros];
+    data.extend(bytes.iter().rev());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // CID of "hello world" added as a raw block
+    const HELLO_CID: &str = "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e";
+
+    fn hello() -> Attachment { Attachment::with_data(MediaType::with("text/plain"), b"hello world") }
+
+    #[test]
+    fn attachment_to_cid() {
+        assert_eq!(hello().to_cid().to_string(), HELLO_CID);
+    }
+
+    #[test]
+    fn cid_to_attachment() {
+        let cid = Cid::from_str(HELLO_CID).unwrap();
+        assert_eq!(cid.version(), CidVersion::V1);
+        assert_eq!(cid.codec(), MULTICODEC_RAW);
+        assert_eq!(Attachment::from_cid(&cid, MediaType::with("text/plain")).unwrap(), hello());
+    }
+
+    #[test]
+    fn multibase_roundtrip() {
+        let cid = hello().to_cid();
+        for base in [Multibase::Base32Lower, Multibase::Base58Btc, Multibase::Base16Lower] {
+            let s = cid.clone().with_multibase(base).to_string();
+            assert!(s.starts_with(base.prefix()));
+            let parsed = Cid::from_str(&s).unwrap();
+            assert_eq!(parsed.multibase(), base);
+            assert_eq!(parsed.to_bytes(), cid.to_bytes());
+        }
+    }
+
+    #[test]
+    fn blake3_cid() {
+        let attachment = AttachmentV1::with_data(MediaType::with("image/png"), DigestAlgo::Blake3, b"png");
+        let cid = attachment.to_cid().unwrap();
+        assert_eq!(cid.hash_fn(), MULTIHASH_BLAKE3);
+        let parsed = Cid::from_str(&cid.to_string()).unwrap();
+        assert_eq!(AttachmentV1::from_cid(&parsed, MediaType::with("image/png")).unwrap(), attachment);
+        assert_eq!(
+            Attachment::from_cid(&parsed, MediaType::with("image/png")),
+            Err(CidError::UnsupportedHash(MULTIHASH_BLAKE3))
+        );
+
+        let tagged = AttachmentV1::with_data(MediaType::with("image/png"), DigestAlgo::TaggedSha256, b"png");
+        assert_eq!(tagged.to_cid(), Err(CidError::UnrepresentableAlgo(DigestAlgo::TaggedSha256)));
+    }
+
+    #[test]
+    fn dag_pb_rejected() {
+        let cid = Cid::from_str("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        assert_eq!(cid.version(), CidVersion::V0);
+        assert_eq!(cid.to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n");
+        assert_eq!(Attachment::from_cid(&cid, MediaType::with("image/png")), Err(CidError::DagPb));
+
+        let mut bytes = vec![0x01, 0x70];
+        bytes.extend(hello().to_cid().to_bytes()[2..].iter());
+        let cid = Cid::from_bytes(&bytes).unwrap();
+        assert_eq!(cid.raw_digest(), Err(CidError::DagPb));
+    }
+
+    #[test]
+    fn unsupported_hash() {
+        // sha2-512 multihash
+        let mut bytes = vec![0x01, 0x55, 0x13, 0x40];
+        bytes.extend([0xAB; 64]);
+        let cid = Cid::from_bytes(&bytes).unwrap();
+        assert_eq!(Attachment::from_cid(&cid, MediaType::with("image/png")), Err(CidError::UnsupportedHash(0x13)));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Cid::from_str(""), Err(CidError::Empty));
+        assert_eq!(Cid::from_str("mAXASIA"), Err(CidError::UnknownMultibase('m')));
+        assert_eq!(
+            Cid::from_str("bafkrei1"),
+            Err(CidError::InvalidChar { base: Multibase::Base32Lower, ch: '1', pos: 7 })
+        );
+        assert_eq!(Cid::from_str(&HELLO_CID[..40]), Err(CidError::NonCanonical(Multibase::Base32Lower)));
+        let hex = hello()
+            .to_cid()
+            .with_multibase(Multibase::Base16Lower)
+            .to_string();
+        assert_eq!(Cid::from_str(&hex[..49]), Err(CidError::MultihashLength { declared: 32, actual: 20 }));
+        assert_eq!(Cid::from_str("bai"), Err(CidError::UnsupportedVersion(2)));
+    }
+}
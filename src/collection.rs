@@ -0,0 +1,232 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+
+use amplify::confinement::{self, SmallOrdMap};
+use amplify::Wrapper;
+use strict_encoding::{StrictDeserialize, StrictDumb, StrictSerialize};
+
+use crate::{AssetName, Details, EmbeddedMedia, NftSpec, TokenNo, LIB_NAME_RGB21};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum CollectionError {
+    /// token {0} is specified more than once.
+    DuplicateToken(TokenNo),
+
+    /// token {token} is out of the collection range, which has {total} tokens.
+    OutOfRange { token: TokenNo, total: u32 },
+
+    /// too many token specifications; {0}
+    #[from]
+    Confinement(confinement::Error),
+}
+
+/// Specification of an NFT collection.
+///
+/// Tokens of the collection are numbered from zero up to (but not including) `total`. Metadata
+/// shared by the tokens are specified once at the collection level; only the tokens which have
+/// individual metadata need to be listed in `tokens`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct NftCollection {
+    pub name: AssetName,
+    pub details: Option<Details>,
+    /// Preview media shared by all tokens which don't have their own embedded media.
+    pub preview: Option<EmbeddedMedia>,
+    /// Total number of tokens in the collection.
+    pub total: u32,
+    /// Individual token specifications.
+    pub tokens: SmallOrdMap<TokenNo, NftSpec>,
+}
+impl StrictSerialize for NftCollection {}
+impl StrictDeserialize for NftCollection {}
+
+impl NftCollection {
+    /// Constructs collection specification, checking token numbers for uniqueness and range.
+    pub fn with(
+        name: AssetName,
+        details: Option<Details>,
+        preview: Option<EmbeddedMedia>,
+        total: u32,
+        tokens: impl IntoIterator<Item = (TokenNo, NftSpec)>,
+    ) -> Result<Self, CollectionError> {
+        let mut map = BTreeMap::new();
+        for (no, spec) in tokens {
+            if map.insert(no, spec).is_some() {
+                return Err(CollectionError::DuplicateToken(no));
+            }
+        }
+        let collection = NftCollection {
+            name,
+            details,
+            preview,
+            total,
+            tokens: SmallOrdMap::try_from(map)?,
+        };
+        collection.validate()?;
+        Ok(collection)
+    }
+
+    /// Checks that all individually specified tokens are within the collection range.
+    pub fn validate(&self) -> Result<(), CollectionError> {
+        match self.tokens.keys().find(|no| !self.contains(**no)) {
+            Some(no) => Err(CollectionError::OutOfRange { token: *no, total: self.total }),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks whether a token number belongs to the collection.
+    pub fn contains(&self, no: TokenNo) -> bool { no.into_inner() < self.total }
+
+    /// Iterates over all token numbers of the collection.
+    pub fn token_numbers(&self) -> impl Iterator<Item = TokenNo> { (0..self.total).map(TokenNo::from) }
+
+    /// Returns individual token specification, if any.
+    pub fn token(&self, no: TokenNo) -> Option<&NftSpec> { self.tokens.get(&no) }
+
+    /// Computes metadata of a token, combining its individual specification with the data shared
+    /// at the collection level:
+    /// - if the token has no name, the collection name is used;
+    /// - if the token has no individual specification or its embedded media is empty, the
+    ///   collection preview is used.
+    ///
+    /// Returns `None` if the token doesn't belong to the collection, or if it has no individual
+    /// specification and the collection has no preview, i.e. there is no media to show for it.
+    pub fn effective_spec(&self, no: TokenNo) -> Option<NftSpec> {
+        if !self.contains(no) {
+            return None;
+        }
+        let spec = match self.tokens.get(&no) {
+            Some(spec) => {
+                let mut spec = spec.clone();
+                if spec.name.is_none() {
+                    spec.name = Some(self.name.clone());
+                }
+                if spec.embedded.data.is_empty() {
+                    if let Some(preview) = &self.preview {
+                        spec.embedded = preview.clone();
+                    }
+                }
+                spec
+            }
+            None => NftSpec {
+                name: Some(self.name.clone()),
+                embedded: self.preview.clone()?,
+                external: None,
+                reserves: None,
+            },
+        };
+        Some(spec)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use amplify::confinement::SmallBlob;
+
+    use super::*;
+    use crate::{Attachment, MediaType};
+
+    fn media(data: &[u8]) -> EmbeddedMedia {
+        EmbeddedMedia {
+            mime: MediaType::with("image/png"),
+            data: SmallBlob::from_checked(data.to_vec()),
+        }
+    }
+
+    fn spec(name: Option<&'static str>, data: &[u8]) -> NftSpec {
+        NftSpec {
+            name: name.map(AssetName::from),
+            embedded: media(data),
+            external: Some(Attachment::with_data(MediaType::with("image/png"), data)),
+            reserves: None,
+        }
+    }
+
+    fn collection() -> NftCollection {
+        NftCollection::with(AssetName::from_str("Pixel Cats").unwrap(), None, Some(media(b"preview")), 100, [
+            (TokenNo::from(7), spec(Some("Cat #7"), b"cat7")),
+            (TokenNo::from(8), spec(None, b"")),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn effective_metadata() {
+        let collection = collection();
+
+        let seven = collection.effective_spec(7.into()).unwrap();
+        assert_eq!(seven.name.unwrap().as_str(), "Cat #7");
+        assert_eq!(seven.embedded, media(b"cat7"));
+
+        let eight = collection.effective_spec(8.into()).unwrap();
+        assert_eq!(eight.name.unwrap().as_str(), "Pixel Cats");
+        assert_eq!(eight.embedded, media(b"preview"));
+        assert!(eight.external.is_some());
+
+        let nine = collection.effective_spec(9.into()).unwrap();
+        assert_eq!(nine.name.unwrap().as_str(), "Pixel Cats");
+        assert_eq!(nine.embedded, media(b"preview"));
+        assert_eq!(nine.external, None);
+
+        assert_eq!(collection.effective_spec(100.into()), None);
+        assert_eq!(collection.token_numbers().count(), 100);
+    }
+
+    #[test]
+    fn no_preview() {
+        let collection = NftCollection::with(AssetName::from_str("Pixel Cats").unwrap(), None, None, 100, [(
+            TokenNo::from(7),
+            spec(Some("Cat #7"), b"cat7"),
+        )])
+        .unwrap();
+        assert_eq!(collection.effective_spec(7.into()).unwrap().embedded, media(b"cat7"));
+        assert_eq!(collection.effective_spec(9.into()), None);
+    }
+
+    #[test]
+    fn validation() {
+        let name = AssetName::from_str("Pixel Cats").unwrap();
+        assert_eq!(
+            NftCollection::with(name.clone(), None, None, 10, [
+                (TokenNo::from(1), spec(None, b"1")),
+                (TokenNo::from(1), spec(None, b"2"))
+            ]),
+            Err(CollectionError::DuplicateToken(1.into()))
+        );
+        assert_eq!(
+            NftCollection::with(name, None, None, 10, [(TokenNo::from(10), spec(None, b"1"))]),
+            Err(CollectionError::OutOfRange { token: 10.into(), total: 10 })
+        );
+    }
+
+    #[test]
+    fn strict_encoding() {
+        let collection = collection();
+        let data = collection.to_strict_serialized::<0xFFFFFF>().unwrap();
+        assert_eq!(NftCollection::from_strict_serialized::<0xFFFFFF>(data).unwrap(), collection);
+    }
+}
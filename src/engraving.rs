@@ -0,0 +1,225 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::confinement::{self, SmallString};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use crate::{EmbeddedMedia, TokenNo, LIB_NAME_RGB21};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum EngravingError {
+    /// engraving #{seq_no} has neither text nor media.
+    Empty { seq_no: u32 },
+
+    /// engraving #{seq_no} is applied to token {found} instead of token {expected}.
+    WrongToken {
+        expected: TokenNo,
+        found: TokenNo,
+        seq_no: u32,
+    },
+
+    /// engraving #{0} is present more than once.
+    DuplicateSeqNo(u32),
+
+    /// engraving #{expected} is missing; the next present one is #{found}.
+    Gap { expected: u32, found: u32 },
+
+    /// engraving history is full.
+    Overflow,
+}
+
+/// Unicode text of an engraving.
+#[derive(Wrapper, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct EngravingText(SmallString);
+
+impl TryFrom<String> for EngravingText {
+    type Error = confinement::Error;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> { SmallString::try_from(text).map(Self) }
+}
+
+/// Message or media engraved onto a token by its owner, usually during a transfer.
+///
+/// Engravings of a token are numbered sequentially starting from zero, so the history of
+/// engravings can be restored in a deterministic order.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct Engraving {
+    pub applied_to: TokenNo,
+    pub seq_no: u32,
+    pub text: Option<EngravingText>,
+    pub media: Option<EmbeddedMedia>,
+}
+impl StrictSerialize for Engraving {}
+impl StrictDeserialize for Engraving {}
+
+impl Engraving {
+    /// Checks that the engraving has some content.
+    pub fn validate(&self) -> Result<(), EngravingError> {
+        if !self.text.as_ref().is_some_and(|text| !text.is_empty()) && self.media.is_none() {
+            return Err(EngravingError::Empty { seq_no: self.seq_no });
+        }
+        Ok(())
+    }
+}
+
+/// Ordered history of the engravings applied to a token.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct EngravingHistory {
+    token: TokenNo,
+    engravings: Vec<Engraving>,
+}
+
+impl EngravingHistory {
+    pub fn new(token: TokenNo) -> Self { EngravingHistory { token, engravings: vec![] } }
+
+    /// Restores history from engravings provided in an arbitrary order, checking that they are
+    /// applied to the same token and have no gaps or repetitions in their sequence numbers.
+    pub fn restore(token: TokenNo, engravings: impl IntoIterator<Item = Engraving>) -> Result<Self, EngravingError> {
+        let mut engravings = engravings.into_iter().collect::<Vec<_>>();
+        engravings.sort_by_key(|engraving| engraving.seq_no);
+        let mut history = Self::new(token);
+        for engraving in engravings {
+            history.push(engraving)?;
+        }
+        Ok(history)
+    }
+
+    /// Appends the next engraving to the history.
+    pub fn push(&mut self, engraving: Engraving) -> Result<(), EngravingError> {
+        engraving.validate()?;
+        if engraving.applied_to != self.token {
+            return Err(EngravingError::WrongToken {
+                expected: self.token,
+                found: engraving.applied_to,
+                seq_no: engraving.seq_no,
+            });
+        }
+        let expected = self.next_seq_no()?;
+        if engraving.seq_no < expected {
+            return Err(EngravingError::DuplicateSeqNo(engraving.seq_no));
+        }
+        if engraving.seq_no > expected {
+            return Err(EngravingError::Gap { expected, found: engraving.seq_no });
+        }
+        self.engravings.push(engraving);
+        Ok(())
+    }
+
+    /// Constructs the next engraving for the token, appending it to the history.
+    pub fn engrave(
+        &mut self,
+        text: Option<EngravingText>,
+        media: Option<EmbeddedMedia>,
+    ) -> Result<&Engraving, EngravingError> {
+        let engraving = Engraving {
+            applied_to: self.token,
+            seq_no: self.next_seq_no()?,
+            text,
+            media,
+        };
+        self.push(engraving)?;
+        Ok(self.engravings.last().expect("just added"))
+    }
+
+    /// Sequence number of the next engraving.
+    pub fn next_seq_no(&self) -> Result<u32, EngravingError> {
+        u32::try_from(self.engravings.len()).map_err(|_| EngravingError::Overflow)
+    }
+
+    pub fn token(&self) -> TokenNo { self.token }
+
+    pub fn latest(&self) -> Option<&Engraving> { self.engravings.last() }
+
+    /// Engravings from the earliest to the latest.
+    pub fn engravings(&self) -> &[Engraving] { &self.engravings }
+
+    pub fn len(&self) -> usize { self.engravings.len() }
+
+    pub fn is_empty(&self) -> bool { self.engravings.is_empty() }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::SmallBlob;
+
+    use super::*;
+    use crate::MediaType;
+
+    fn engraving(token: u32, seq_no: u32, text: &str) -> Engraving {
+        Engraving {
+            applied_to: token.into(),
+            seq_no,
+            text: Some(EngravingText::try_from(text.to_owned()).unwrap()),
+            media: None,
+        }
+    }
+
+    #[test]
+    fn history() {
+        let mut history =
+            EngravingHistory::restore(5.into(), [engraving(5, 1, "second owner"), engraving(5, 0, "first owner")])
+                .unwrap();
+        let media = EmbeddedMedia {
+            mime: MediaType::with("image/png"),
+            data: SmallBlob::from_checked(b"signature".to_vec()),
+        };
+        let latest = history.engrave(None, Some(media.clone())).unwrap();
+        assert_eq!(latest.seq_no, 2);
+        assert_eq!(latest.media, Some(media));
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history.engravings()[0]
+                .text
+                .as_ref()
+                .map(|text| text.as_str()),
+            Some("first owner")
+        );
+    }
+
+    #[test]
+    fn inconsistency() {
+        assert_eq!(
+            EngravingHistory::restore(5.into(), [engraving(5, 0, "a"), engraving(5, 0, "b")]),
+            Err(EngravingError::DuplicateSeqNo(0))
+        );
+        assert_eq!(
+            EngravingHistory::restore(5.into(), [engraving(5, 0, "a"), engraving(5, 2, "b")]),
+            Err(EngravingError::Gap { expected: 1, found: 2 })
+        );
+        assert_eq!(
+            EngravingHistory::restore(5.into(), [engraving(6, 0, "a")]),
+            Err(EngravingError::WrongToken { expected: 5.into(), found: 6.into(), seq_no: 0 })
+        );
+        assert_eq!(
+            EngravingHistory::new(5.into())
+                .engrave(None, None)
+                .map(|_| ()),
+            Err(EngravingError::Empty { seq_no: 0 })
+        );
+    }
+}
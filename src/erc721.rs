@@ -0,0 +1,594 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Conversion between ERC-721 (OpenSea) token metadata JSON and RGB21 NFT specification.
+//!
+//! Media mapping:
+//! - `image` becomes the embedded media, if its data are available (a local file or a `data:` URI)
+//!   and fit into the embedded media size limit;
+//! - `animation_url` becomes the external attachment; if there is no animation, an image which
+//!   can't be embedded becomes the external attachment instead;
+//! - `description` becomes contract details.
+//!
+//! Remote media can't be committed to without fetching them, except for IPFS raw CIDs, which
+//! already contain the content digest. For the rest, only a locator is kept and the loss is
+//! reported.
+
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+use amplify::confinement::SmallBlob;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{Map, Value};
+use strict_encoding::StrictDumb;
+
+use crate::{
+    AssetName, Attachment, AttrJsonError, Details, EmbeddedMedia, MediaLocator, MediaLocators, MediaType,
+    NftAttributes, NftSpec,
+};
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+#[derive(Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum Erc721Error {
+    /// unable to read media file '{path}'; {err}
+    Io { path: String, err: io::Error },
+
+    /// invalid data URI in the `{0}` field.
+    DataUri(Erc721Field),
+
+    /// invalid media type '{1}' in the `{0}` field.
+    MediaType(Erc721Field, String),
+
+    /// `{field}` references local file '{path}' outside of the base directory.
+    PathOutsideBase { field: Erc721Field, path: String },
+}
+
+/// Media fields of the ERC-721 metadata.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display)]
+pub enum Erc721Field {
+    #[display("image")]
+    Image,
+
+    #[display("animation_url")]
+    AnimationUrl,
+}
+
+/// Metadata which can't be represented losslessly during the conversion.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(doc_comments)]
+pub enum Erc721Loss {
+    /// name '{0}' is not a valid asset name and is dropped.
+    Name(String),
+
+    /// description is not a valid contract details string and is dropped.
+    Description,
+
+    /// `{field}` references remote media '{uri}' which is not committed to; only its locator is
+    /// kept.
+    Uncommitted { field: Erc721Field, uri: String },
+
+    /// `{0}` media is dropped since both the embedded and external media slots are taken.
+    Dropped(Erc721Field),
+
+    /// media type of `{0}` is unknown and is set to application/octet-stream.
+    UnknownMediaType(Erc721Field),
+
+    /// `{0}` URI is not a valid media locator and is dropped.
+    Locator(Erc721Field),
+
+    /// attributes are dropped; {0}
+    Attributes(AttrJsonError),
+
+    /// field `{0}` has no RGB21 counterpart and is dropped.
+    Field(String),
+
+    /// proof of reserves has no ERC-721 counterpart and is dropped.
+    Reserves,
+}
+
+/// ERC-721 token metadata JSON, as used by OpenSea and other marketplaces.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Erc721Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Value>,
+    /// Non-standard fields.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Result of the ERC-721 metadata import.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Erc721Import {
+    pub spec: NftSpec,
+    pub details: Option<Details>,
+    pub attributes: NftAttributes,
+    /// Locators of the external media, if it is remote.
+    pub locators: MediaLocators,
+    /// Metadata which were not converted losslessly.
+    pub losses: Vec<Erc721Loss>,
+}
+
+enum Media {
+    /// Media data are available.
+    Data(MediaType, Vec<u8>),
+    /// Media is remote, but its content is committed to by the locator.
+    Committed(Attachment, MediaLocator),
+    /// Remote media which can't be committed to without fetching it.
+    Remote(MediaLocator),
+}
+
+impl Erc721Metadata {
+    /// Converts the metadata into RGB21 NFT specification. Local media files are resolved relative
+    /// to `base_dir` and their data are embedded or committed to; files outside of `base_dir` are
+    /// rejected.
+    pub fn to_spec(&self, base_dir: impl AsRef<Path>) -> Result<Erc721Import, Erc721Error> {
+        let base_dir = base_dir.as_ref();
+        let mut losses = vec![];
+
+        let name = self
+            .name
+            .as_ref()
+            .and_then(|name| match AssetName::from_str(name) {
+                Ok(name) => Some(name),
+                Err(_) => {
+                    losses.push(Erc721Loss::Name(name.clone()));
+                    None
+                }
+            });
+        let details = self
+            .description
+            .as_ref()
+            .and_then(|descr| match Details::from_str(descr) {
+                Ok(details) => Some(details),
+                Err(_) => {
+                    losses.push(Erc721Loss::Description);
+                    None
+                }
+            });
+        let attributes = match &self.attributes {
+            None => NftAttributes::default(),
+            Some(json) => NftAttributes::from_erc721_json(json).unwrap_or_else(|err| {
+                losses.push(Erc721Loss::Attributes(err));
+                NftAttributes::default()
+            }),
+        };
+        if self.external_url.is_some() {
+            losses.push(Erc721Loss::Field(s!("external_url")));
+        }
+        losses.extend(self.other.keys().cloned().map(Erc721Loss::Field));
+
+        let image = self
+            .image
+            .as_deref()
+            .map(|uri| load_media(Erc721Field::Image, uri, base_dir, &mut losses))
+            .transpose()?
+            .flatten();
+        let animation = self
+            .animation_url
+            .as_deref()
+            .map(|uri| load_media(Erc721Field::AnimationUrl, uri, base_dir, &mut losses))
+            .transpose()?
+            .flatten();
+
+        let mut embedded = None;
+        let mut external = None;
+        if let Some((_, media)) = animation {
+            external = Some((Erc721Field::AnimationUrl, media));
+        }
+        match image {
+            Some((_, Media::Data(mime, data))) if data.len() <= u16::MAX as usize => {
+                embedded = Some(EmbeddedMedia { mime, data: SmallBlob::from_checked(data) });
+            }
+            Some((field, media)) if external.is_none() => external = Some((field, media)),
+            Some((field, _)) => losses.push(Erc721Loss::Dropped(field)),
+            None => {}
+        }
+
+        let mut locators = MediaLocators::default();
+        let external = external.and_then(|(field, media)| match media {
+            Media::Data(mime, data) => Some(Attachment::with_data(mime, data)),
+            Media::Committed(attachment, locator) => {
+                locators.push(locator).expect("single locator");
+                Some(attachment)
+            }
+            Media::Remote(locator) => {
+                losses.push(Erc721Loss::Uncommitted { field, uri: locator.to_string() });
+                locators.push(locator).expect("single locator");
+                None
+            }
+        });
+
+        let embedded = embedded.unwrap_or_else(|| EmbeddedMedia {
+            mime: MediaType::with(OCTET_STREAM),
+            data: SmallBlob::strict_dumb(),
+        });
+
+        Ok(Erc721Import {
+            spec: NftSpec { name, embedded, external, reserves: None },
+            details,
+            attributes,
+            locators,
+            losses,
+        })
+    }
+
+    /// Converts RGB21 NFT specification into the ERC-721 metadata.
+    ///
+    /// Embedded media is exported as a `data:` URI. External media is referenced by its first
+    /// locator or, if there are no locators, by its raw IPFS CID. The external media is exported as
+    /// `image` if there is no embedded media and it is an image, and as `animation_url` otherwise.
+    pub fn from_spec(
+        spec: &NftSpec,
+        details: Option<&Details>,
+        attributes: &NftAttributes,
+        locators: &MediaLocators,
+    ) -> (Self, Vec<Erc721Loss>) {
+        let mut losses = vec![];
+        if spec.reserves.is_some() {
+            losses.push(Erc721Loss::Reserves);
+        }
+
+        let mut image = None;
+        let mut animation_url = None;
+        if !spec.embedded.data.is_empty() {
+            image = Some(format!("data:{};base64,{}", spec.embedded.mime, BASE64.encode(&spec.embedded.data)));
+        }
+        if let Some(external) = &spec.external {
+            let uri = match locators.first() {
+                Some(locator) => locator.to_string(),
+                None => MediaLocator::ipfs(&external.to_cid()).to_string(),
+            };
+            if image.is_none() && external.mime.ty.as_str() == "image" {
+                image = Some(uri);
+            } else {
+                animation_url = Some(uri);
+            }
+        }
+
+        let metadata = Erc721Metadata {
+            name: spec.name.as_ref().map(AssetName::to_string),
+            description: details.map(Details::to_string),
+            image,
+            animation_url,
+            external_url: None,
+            attributes: if attributes.is_empty() { None } else { Some(attributes.to_erc721_json()) },
+            other: none!(),
+        };
+        (metadata, losses)
+    }
+}
+
+impl Display for Erc721Metadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let json = if f.alternate() { serde_json::to_string_pretty(self) } else { serde_json::to_string(self) };
+        f.write_str(&json.map_err(|_| fmt::Error)?)
+    }
+}
+
+impl FromStr for Erc721Metadata {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { serde_json::from_str(s) }
+}
+
+/// Guesses media type from the file extension in a path or URI.
+pub fn media_type_by_extension(path: &str) -> Option<MediaType> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let (_, ext) = path.rsplit_once('.')?;
+    if ext.contains('/') {
+        return None;
+    }
+    Some(MediaType::with(match ext.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "avif" => "image/avif",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "glb" => "model/gltf-binary",
+        "gltf" => "model/gltf+json",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "txt" => "text/plain",
+        _ => return None,
+    }))
+}
+
+fn media_type_or_default(field: Erc721Field, mime: Option<MediaType>, losses: &mut Vec<Erc721Loss>) -> MediaType {
+    mime.unwrap_or_else(|| {
+        losses.push(Erc721Loss::UnknownMediaType(field));
+        MediaType::with(OCTET_STREAM)
+    })
+}
+
+fn load_media(
+    field: Erc721Field,
+    uri: &str,
+    base_dir: &Path,
+    losses: &mut Vec<Erc721Loss>,
+) -> Result<Option<(Erc721Field, Media)>, Erc721Error> {
+    if let Some(data_uri) = uri.strip_prefix("data:") {
+        let (mime, data) = parse_data_uri(field, data_uri)?;
+        return Ok(Some((field, Media::Data(mime, data))));
+    }
+
+    let local = uri
+        .strip_prefix("file://")
+        .or_else(|| (!uri.contains(':')).then_some(uri));
+    if let Some(path) = local {
+        let path = local_path(field, base_dir, path)?;
+        let data = std::fs::read(&path).map_err(|err| Erc721Error::Io { path: path.display().to_string(), err })?;
+        let mime = media_type_or_default(field, media_type_by_extension(uri), losses);
+        return Ok(Some((field, Media::Data(mime, data))));
+    }
+
+    let Ok(locator) = MediaLocator::from_str(uri) else {
+        losses.push(Erc721Loss::Locator(field));
+        return Ok(None);
+    };
+    let Some(cid) = locator.cid().and_then(Result::ok) else {
+        return Ok(Some((field, Media::Remote(locator))));
+    };
+    let mime = media_type_or_default(field, media_type_by_extension(uri), losses);
+    Ok(Some((field, match Attachment::from_cid(&cid, mime) {
+        Ok(attachment) => Media::Committed(attachment, locator),
+        Err(_) => Media::Remote(locator),
+    })))
+}
+
+/// Resolves path of a local media file, ensuring it doesn't escape the base directory: absolute
+/// paths and `..` components are rejected, and the canonical path (with symlinks resolved) must
+/// remain inside the canonical base directory.
+fn local_path(field: Erc721Field, base_dir: &Path, path: &str) -> Result<PathBuf, Erc721Error> {
+    let outside = || Erc721Error::PathOutsideBase { field, path: path.to_owned() };
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+    let canonicalize = |path: PathBuf| {
+        path.canonicalize()
+            .map_err(|err| Erc721Error::Io { path: path.display().to_string(), err })
+    };
+    let base = canonicalize(base_dir.to_path_buf())?;
+    let full = canonicalize(base.join(relative))?;
+    if !full.starts_with(&base) {
+        return Err(outside());
+    }
+    Ok(full)
+}
+
+fn parse_data_uri(field: Erc721Field, s: &str) -> Result<(MediaType, Vec<u8>), Erc721Error> {
+    let (header, payload) = s.split_once(',').ok_or(Erc721Error::DataUri(field))?;
+    let mut params = header.split(';');
+    let mime = match params.next().unwrap_or_default() {
+        "" => MediaType::with("text/plain"),
+        mime => MediaType::from_str(mime).map_err(|_| Erc721Error::MediaType(field, mime.to_owned()))?,
+    };
+    let data = if params.any(|param| param == "base64") {
+        BASE64
+            .decode(payload)
+            .map_err(|_| Erc721Error::DataUri(field))?
+    } else {
+        percent_decode(payload).ok_or(Erc721Error::DataUri(field))?
+    };
+    Ok((mime, data))
+}
+
+fn percent_decode(s: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        if b != b'%' {
+            data.push(b);
+            continue;
+        }
+        let hi = (bytes.next()? as char).to_digit(16)?;
+        let lo = (bytes.next()? as char).to_digit(16)?;
+        data.push((hi * 16 + lo) as u8);
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::AttrValue;
+
+    fn tmp_dir(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rgb-erc721-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn import_local() {
+        let dir = tmp_dir("local");
+        fs::write(dir.join("cat.png"), b"small preview").unwrap();
+        fs::write(dir.join("cat.mp4"), b"full video").unwrap();
+
+        let metadata = Erc721Metadata::from_str(
+            r#"{
+                "name": "Cat #1",
+                "description": "A pixel cat",
+                "image": "cat.png",
+                "animation_url": "file://cat.mp4",
+                "external_url": "https://cats.example/1",
+                "attributes": [{ "trait_type": "Eyes", "value": "Laser" }],
+                "background_color": "ffffff"
+            }"#,
+        )
+        .unwrap();
+        let import = metadata.to_spec(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(import.spec.name.as_ref().unwrap().as_str(), "Cat #1");
+        assert_eq!(import.details.as_ref().unwrap().as_str(), "A pixel cat");
+        assert_eq!(import.spec.embedded.mime, MediaType::with("image/png"));
+        assert_eq!(import.spec.embedded.data.as_slice(), b"small preview");
+        assert_eq!(import.spec.external, Some(Attachment::with_data(MediaType::with("video/mp4"), b"full video")));
+        assert!(import.locators.is_empty());
+        assert_eq!(import.attributes.get("Eyes"), Some(&AttrValue::Text(tiny_s!("Laser"))));
+        assert_eq!(import.losses, vec![
+            Erc721Loss::Field(s!("external_url")),
+            Erc721Loss::Field(s!("background_color"))
+        ]);
+    }
+
+    #[test]
+    fn path_traversal() {
+        let dir = tmp_dir("traversal");
+        let base = dir.join("base");
+        fs::create_dir_all(&base).unwrap();
+        fs::write(dir.join("secret.png"), b"secret").unwrap();
+        let secret = dir.join("secret.png").display().to_string();
+
+        for uri in [s!("../secret.png"), s!("file://../secret.png"), s!("./sub/../../secret.png"), secret.clone()] {
+            let metadata = Erc721Metadata { image: Some(uri.clone()), ..default!() };
+            assert!(
+                matches!(
+                    metadata.to_spec(&base),
+                    Err(Erc721Error::PathOutsideBase { field: Erc721Field::Image, path }) if uri.ends_with(&path)
+                ),
+                "{uri}"
+            );
+        }
+        let metadata = Erc721Metadata { image: Some(format!("file://{secret}")), ..default!() };
+        assert!(matches!(metadata.to_spec(&base), Err(Erc721Error::PathOutsideBase { .. })));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("secret.png"), base.join("link.png")).unwrap();
+            let metadata = Erc721Metadata { image: Some(s!("link.png")), ..default!() };
+            assert!(matches!(metadata.to_spec(&base), Err(Erc721Error::PathOutsideBase { .. })));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn import_remote() {
+        let metadata = Erc721Metadata {
+            name: Some(s!("Cat #2 from the Very Long Named Collection of Pixel Cats")),
+            image: Some(s!("https://cats.example/2.png")),
+            animation_url: Some(s!("ipfs://bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e")),
+            ..default!()
+        };
+        let import = metadata.to_spec("").unwrap();
+        let external = import.spec.external.unwrap();
+        assert_eq!(external, Attachment::with_data(MediaType::with(OCTET_STREAM), b"hello world"));
+        assert_eq!(import.locators.len(), 1);
+        assert!(import.spec.embedded.data.is_empty());
+        assert_eq!(import.losses, vec![
+            Erc721Loss::Name(s!("Cat #2 from the Very Long Named Collection of Pixel Cats")),
+            Erc721Loss::UnknownMediaType(Erc721Field::AnimationUrl),
+            Erc721Loss::Dropped(Erc721Field::Image),
+        ]);
+
+        let metadata = Erc721Metadata { image: Some(s!("https://cats.example/2.png")), ..default!() };
+        let import = metadata.to_spec("").unwrap();
+        assert_eq!(import.spec.external, None);
+        assert_eq!(import.locators[0].to_string(), "https://cats.example/2.png");
+        assert_eq!(import.losses, vec![Erc721Loss::Uncommitted {
+            field: Erc721Field::Image,
+            uri: s!("https://cats.example/2.png")
+        }]);
+    }
+
+    #[test]
+    fn data_uri() {
+        let metadata = Erc721Metadata {
+            image: Some(s!("data:image/svg+xml;utf8,%3Csvg%2F%3E")),
+            ..default!()
+        };
+        let import = metadata.to_spec("").unwrap();
+        assert_eq!(import.spec.embedded.mime, MediaType::with("image/svg+xml"));
+        assert_eq!(import.spec.embedded.data.as_slice(), b"<svg/>");
+
+        let metadata = Erc721Metadata { image: Some(s!("data:image/png;base64,???")), ..default!() };
+        assert!(matches!(metadata.to_spec(""), Err(Erc721Error::DataUri(Erc721Field::Image))));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let spec = NftSpec {
+            name: Some(AssetName::from("Cat #3")),
+            embedded: EmbeddedMedia {
+                mime: MediaType::with("image/png"),
+                data: SmallBlob::from_checked(b"preview".to_vec()),
+            },
+            external: Some(Attachment::with_data(MediaType::with("video/mp4"), b"video")),
+            reserves: None,
+        };
+        let details = Details::from_str("Third cat").unwrap();
+        let attributes = NftAttributes::from_erc721_json(&serde_json::json!([
+            { "trait_type": "Eyes", "value": "Normal" },
+            { "display_type": "number", "trait_type": "Generation", "value": 3 }
+        ]))
+        .unwrap();
+        let locators = MediaLocators::with([MediaLocator::https("https://cats.example/3.mp4").unwrap()]).unwrap();
+
+        let (metadata, losses) = Erc721Metadata::from_spec(&spec, Some(&details), &attributes, &locators);
+        assert!(losses.is_empty());
+        assert_eq!(metadata.image.as_deref(), Some("data:image/png;base64,cHJldmlldw=="));
+        assert_eq!(metadata.animation_url.as_deref(), Some("https://cats.example/3.mp4"));
+
+        let json = metadata.to_string();
+        let import = Erc721Metadata::from_str(&json)
+            .unwrap()
+            .to_spec("")
+            .unwrap();
+        assert_eq!(import.spec.name, spec.name);
+        assert_eq!(import.spec.embedded, spec.embedded);
+        assert_eq!(import.details, Some(details));
+        assert_eq!(import.attributes, attributes);
+        assert_eq!(import.locators, locators);
+        assert_eq!(import.spec.external, None);
+        assert_eq!(import.losses, vec![Erc721Loss::Uncommitted {
+            field: Erc721Field::AnimationUrl,
+            uri: s!("https://cats.example/3.mp4")
+        }]);
+    }
+}
@@ -0,0 +1,395 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use amplify::confinement::Confined;
+use strict_encoding::{DecodeError, ReadTuple, StrictDecode, StrictDeserialize, StrictSerialize, TypedRead};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::{AssetName, LIB_NAME_RGB_CONTRACT};
+
+/// Maximal length of an internationalized name, in bytes of its UTF-8 representation.
+pub const INTL_NAME_MAX_LEN: usize = 0xFF;
+
+/// Maximal number of characters in an [`AssetName`].
+const ASSET_NAME_MAX_CHARS: usize = 40;
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum IntlNameError {
+    /// name is empty.
+    Empty,
+
+    /// name is {0} bytes long in NFC form, exceeding the maximum of 255 bytes.
+    TooLong(usize),
+
+    /// name is not in Unicode normalization form C.
+    NotNormalized,
+
+    /// name contains control character U+{code:04X} at position {pos}.
+    Control { code: u32, pos: usize },
+
+    /// name contains bidirectional formatting character U+{code:04X} at position {pos}.
+    Bidi { code: u32, pos: usize },
+
+    /// name contains invisible character U+{code:04X} at position {pos}.
+    Invisible { code: u32, pos: usize },
+
+    /// name mixes {0} and {1} scripts, which is a common spoofing technique.
+    MixedScripts(NameScript, NameScript),
+
+    /// name '{0}' has no ASCII representation, so an explicit ASCII name must be provided.
+    NoAsciiFallback(String),
+}
+
+/// Writing system of a name character, as used in mixed-script detection.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[display(lowercase)]
+pub enum NameScript {
+    /// Digits, punctuation, symbols and other characters shared by all scripts.
+    Common,
+    /// Combining marks, which inherit the script of the preceding character.
+    Inherited,
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Tamil,
+    Thai,
+    Georgian,
+    Hangul,
+    Hiragana,
+    Katakana,
+    Han,
+    /// Letters of scripts which are not distinguished by this library.
+    Other,
+}
+
+impl NameScript {
+    /// Detects the script of a character using the main Unicode blocks of each script.
+    pub fn of(c: char) -> NameScript {
+        if is_combining_mark(c) {
+            return NameScript::Inherited;
+        }
+        if !c.is_alphabetic() {
+            return NameScript::Common;
+        }
+        match c as u32 {
+            0x0041..=0x005A
+            | 0x0061..=0x007A
+            | 0x00AA
+            | 0x00BA
+            | 0x00C0..=0x024F
+            | 0x1E00..=0x1EFF
+            | 0x2C60..=0x2C7F
+            | 0xA720..=0xA7FF
+            | 0xAB30..=0xAB6F
+            | 0xFF21..=0xFF3A
+            | 0xFF41..=0xFF5A => NameScript::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => NameScript::Greek,
+            0x0400..=0x052F | 0x1C80..=0x1C8F | 0x2DE0..=0x2DFF | 0xA640..=0xA69F => NameScript::Cyrillic,
+            0x0530..=0x058F => NameScript::Armenian,
+            0x0590..=0x05FF => NameScript::Hebrew,
+            0x0600..=0x06FF | 0x0750..=0x077F | 0x08A0..=0x08FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => {
+                NameScript::Arabic
+            }
+            0x0900..=0x097F => NameScript::Devanagari,
+            0x0980..=0x09FF => NameScript::Bengali,
+            0x0B80..=0x0BFF => NameScript::Tamil,
+            0x0E00..=0x0E7F => NameScript::Thai,
+            0x10A0..=0x10FF | 0x1C90..=0x1CBF => NameScript::Georgian,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => NameScript::Hangul,
+            0x3040..=0x309F => NameScript::Hiragana,
+            0x30A0..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => NameScript::Katakana,
+            0x3005 | 0x3007 | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x3FFFF => {
+                NameScript::Han
+            }
+            _ => NameScript::Other,
+        }
+    }
+}
+
+/// Script combinations which are used by real languages and are allowed in a single name.
+const SCRIPT_COMBINATIONS: &[&[NameScript]] = &[
+    &[NameScript::Latin, NameScript::Han, NameScript::Hiragana, NameScript::Katakana],
+    &[NameScript::Latin, NameScript::Han, NameScript::Hangul],
+];
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+fn is_invisible(c: char) -> bool { matches!(c, '\u{00AD}' | '\u{200B}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}') }
+
+/// Letters which have no canonical decomposition into an ASCII letter and a diacritic mark.
+fn ascii_transliteration(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ø' => "O",
+        'ø' => "o",
+        'Đ' => "D",
+        'đ' => "d",
+        'Ł' => "L",
+        'ł' => "l",
+        'Þ' => "Th",
+        'þ' => "th",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+/// Internationalized name: a bounded Unicode string in normalization form C.
+///
+/// Unlike [`AssetName`], the name may use any script, but it can't contain control, invisible or
+/// bidirectional formatting characters, and it can't mix scripts unless they are commonly used
+/// together (like Latin with Han and Kana in Japanese), preventing names from imitating other
+/// names with lookalike characters.
+#[derive(Wrapper, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
+#[wrapper(Deref, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT, dumb = IntlName(Confined::from_checked(s!("?"))))]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(transparent))]
+pub struct IntlName(Confined<String, 1, INTL_NAME_MAX_LEN>);
+
+impl StrictSerialize for IntlName {}
+impl StrictDeserialize for IntlName {}
+
+impl StrictDecode for IntlName {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_tuple(|r| {
+            let name: Confined<String, 1, INTL_NAME_MAX_LEN> = r.read_field()?;
+            Self::check(&name).map_err(|err| DecodeError::DataIntegrityError(err.to_string()))?;
+            Ok(IntlName(name))
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for IntlName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+        IntlName::from_str(&name).map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for IntlName {
+    type Err = IntlNameError;
+
+    /// Normalizes the name into NFC and checks it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.nfc().collect::<String>();
+        let len = name.len();
+        let name = Confined::try_from(name).map_err(|_| match len {
+            0 => IntlNameError::Empty,
+            len => IntlNameError::TooLong(len),
+        })?;
+        Self::check(&name)?;
+        Ok(IntlName(name))
+    }
+}
+
+impl IntlName {
+    fn check(name: &str) -> Result<(), IntlNameError> {
+        if !is_nfc(name) {
+            return Err(IntlNameError::NotNormalized);
+        }
+        let mut scripts = BTreeSet::new();
+        for (pos, c) in name.chars().enumerate() {
+            let code = c as u32;
+            if c.is_control() {
+                return Err(IntlNameError::Control { code, pos });
+            }
+            if is_bidi_control(c) {
+                return Err(IntlNameError::Bidi { code, pos });
+            }
+            if is_invisible(c) {
+                return Err(IntlNameError::Invisible { code, pos });
+            }
+            match NameScript::of(c) {
+                NameScript::Common | NameScript::Inherited => {}
+                script => {
+                    scripts.insert(script);
+                }
+            }
+        }
+        if scripts.len() > 1
+            && !SCRIPT_COMBINATIONS
+                .iter()
+                .any(|allowed| scripts.iter().all(|script| allowed.contains(script)))
+        {
+            let mut scripts = scripts.into_iter();
+            let first = scripts.next().expect("more than one script");
+            let second = scripts.next().expect("more than one script");
+            return Err(IntlNameError::MixedScripts(first, second));
+        }
+        Ok(())
+    }
+
+    /// Scripts used by the name, not counting characters common to all scripts.
+    pub fn scripts(&self) -> BTreeSet<NameScript> {
+        self.chars()
+            .map(NameScript::of)
+            .filter(|script| !matches!(script, NameScript::Common | NameScript::Inherited))
+            .collect()
+    }
+
+    /// Checks whether the name consists of ASCII characters only.
+    pub fn is_ascii(&self) -> bool { self.0.is_ascii() }
+
+    /// Derives an ASCII name by removing diacritic marks and transliterating a few Latin letters.
+    ///
+    /// Returns `None` if the name contains non-Latin letters or other characters without an ASCII
+    /// counterpart; such names require an explicit ASCII name provided by the issuer. Names longer
+    /// than 40 characters are truncated.
+    pub fn ascii_fallback(&self) -> Option<AssetName> {
+        let mut ascii = String::with_capacity(self.len());
+        for c in self.nfkd() {
+            if is_combining_mark(c) {
+                continue;
+            }
+            if c.is_ascii() && !c.is_ascii_control() {
+                ascii.push(c);
+            } else if let Some(s) = ascii_transliteration(c) {
+                ascii.push_str(s);
+            } else {
+                return None;
+            }
+        }
+        let ascii = ascii.chars().take(ASSET_NAME_MAX_CHARS).collect::<String>();
+        AssetName::from_str(&ascii).ok()
+    }
+}
+
+/// Internationalized asset name with an explicit name for ASCII-only displays.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct IntlAssetName {
+    pub name: IntlName,
+    pub ascii: AssetName,
+}
+
+impl StrictSerialize for IntlAssetName {}
+impl StrictDeserialize for IntlAssetName {}
+
+impl IntlAssetName {
+    pub fn new(name: IntlName, ascii: AssetName) -> Self { IntlAssetName { name, ascii } }
+
+    /// Constructs the name using [`IntlName::ascii_fallback`] for ASCII-only displays.
+    pub fn with_fallback(name: IntlName) -> Result<Self, IntlNameError> {
+        let ascii = name
+            .ascii_fallback()
+            .ok_or_else(|| IntlNameError::NoAsciiFallback(name.to_string()))?;
+        Ok(IntlAssetName { name, ascii })
+    }
+
+    /// Name to display, depending on whether the display supports Unicode.
+    pub fn display_name(&self, unicode: bool) -> &str {
+        if unicode {
+            self.name.as_str()
+        } else {
+            self.ascii.as_str()
+        }
+    }
+}
+
+impl From<AssetName> for IntlAssetName {
+    fn from(ascii: AssetName) -> Self {
+        let name = IntlName::from_str(ascii.as_str()).expect("ASCII printable asset names are valid names");
+        IntlAssetName { name, ascii }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn name(s: &str) -> IntlName { IntlName::from_str(s).unwrap() }
+
+    #[test]
+    fn normalization() {
+        let decomposed = "Cafe\u{0301} Token";
+        let name = name(decomposed);
+        assert_eq!(name.as_str(), "Café Token");
+        assert_ne!(name.len(), decomposed.len());
+        assert_eq!(name.ascii_fallback().unwrap().as_str(), "Cafe Token");
+        assert!(!name.is_ascii());
+    }
+
+    #[test]
+    fn rejection() {
+        assert_eq!(IntlName::from_str(""), Err(IntlNameError::Empty));
+        assert_eq!(IntlName::from_str(&"ж".repeat(128)), Err(IntlNameError::TooLong(256)));
+        assert_eq!(IntlName::from_str("Tok\nen"), Err(IntlNameError::Control { code: 0x0A, pos: 3 }));
+        assert_eq!(IntlName::from_str("USD\u{202E}T"), Err(IntlNameError::Bidi { code: 0x202E, pos: 3 }));
+        assert_eq!(IntlName::from_str("US\u{200B}DT"), Err(IntlNameError::Invisible { code: 0x200B, pos: 2 }));
+        // Cyrillic "а" imitating the Latin one
+        assert_eq!(
+            IntlName::from_str("P\u{0430}yPal"),
+            Err(IntlNameError::MixedScripts(NameScript::Latin, NameScript::Cyrillic))
+        );
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(name("Рубль 2.0").scripts(), bset![NameScript::Cyrillic]);
+        assert_eq!(name("東京トークン Tokyo").scripts(), bset![
+            NameScript::Latin,
+            NameScript::Katakana,
+            NameScript::Han
+        ]);
+        assert!(IntlName::from_str("Ελληνικά token").is_err());
+        assert_eq!(name("Рубль").ascii_fallback(), None);
+        assert_eq!(name("Straße Œuvre").ascii_fallback().unwrap().as_str(), "Strasse OEuvre");
+    }
+
+    #[test]
+    fn fallback() {
+        assert_eq!(IntlAssetName::with_fallback(name("東京")), Err(IntlNameError::NoAsciiFallback(s!("東京"))));
+        let named = IntlAssetName::new(name("東京"), AssetName::from_str("Tokyo").unwrap());
+        assert_eq!(named.display_name(true), "東京");
+        assert_eq!(named.display_name(false), "Tokyo");
+        let ascii = IntlAssetName::from(AssetName::from_str("Tether USD").unwrap());
+        assert_eq!(ascii.name.as_str(), "Tether USD");
+    }
+
+    #[test]
+    fn strict_encoding() {
+        let named = IntlAssetName::with_fallback(name("Ünïcödé")).unwrap();
+        let data = named.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(IntlAssetName::from_strict_serialized::<0xFFFF>(data).unwrap(), named);
+
+        let decomposed = IntlName(Confined::from_checked(s!("Cafe\u{0301}")));
+        let data = decomposed.to_strict_serialized::<0xFFFF>().unwrap();
+        assert!(IntlName::from_strict_serialized::<0xFFFF>(data).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+
+use crate::{OwnedNft, TokenFractions, TokenNo};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum LedgerError {
+    /// token {0} is not known to the ledger.
+    UnknownToken(TokenNo),
+
+    /// token {0} is already registered.
+    DuplicateToken(TokenNo),
+
+    /// token {0} must have a non-zero number of fractions.
+    ZeroTotal(TokenNo),
+
+    /// allocation of zero fractions of token {0}.
+    ZeroAllocation(TokenNo),
+
+    /// token {token} has {total} fractions, but {allocated} fractions are allocated.
+    OverAllocation {
+        token: TokenNo,
+        total: TokenFractions,
+        allocated: u128,
+    },
+
+    /// token {token} has {total} fractions, but only {allocated} fractions are allocated.
+    UnderAllocation {
+        token: TokenNo,
+        total: TokenFractions,
+        allocated: TokenFractions,
+    },
+
+    /// holder has {available} fractions of token {token}, which is less than {requested}.
+    InsufficientFractions {
+        token: TokenNo,
+        available: TokenFractions,
+        requested: TokenFractions,
+    },
+}
+
+/// Ledger of fractional NFT ownership.
+///
+/// Tracks the total number of fractions of each token and the fractions owned by each holder,
+/// guaranteeing that the allocated fractions never exceed the token total. Holder type is
+/// defined by the user; it may be a single-use seal, an account or a wallet descriptor.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FractionLedger<H: Ord> {
+    totals: BTreeMap<TokenNo, TokenFractions>,
+    holdings: BTreeMap<TokenNo, BTreeMap<H, TokenFractions>>,
+}
+
+impl<H: Ord> Default for FractionLedger<H> {
+    fn default() -> Self { Self { totals: none!(), holdings: none!() } }
+}
+
+impl<H: Ord + Clone> FractionLedger<H> {
+    pub fn new() -> Self { Self::default() }
+
+    /// Constructs ledger from token totals and a complete set of allocations, checking that each
+    /// token is allocated exactly in full.
+    pub fn with(
+        totals: impl IntoIterator<Item = (TokenNo, TokenFractions)>,
+        allocations: impl IntoIterator<Item = (H, OwnedNft)>,
+    ) -> Result<Self, LedgerError> {
+        let mut ledger = Self::new();
+        for (token, total) in totals {
+            ledger.register(token, total)?;
+        }
+        for (holder, nft) in allocations {
+            ledger.allocate(holder, nft)?;
+        }
+        ledger.check_complete()?;
+        Ok(ledger)
+    }
+
+    /// Registers a token with the given total number of fractions.
+    pub fn register(&mut self, token: TokenNo, total: TokenFractions) -> Result<(), LedgerError> {
+        if total == TokenFractions::ZERO {
+            return Err(LedgerError::ZeroTotal(token));
+        }
+        if self.totals.contains_key(&token) {
+            return Err(LedgerError::DuplicateToken(token));
+        }
+        self.totals.insert(token, total);
+        self.holdings.insert(token, none!());
+        Ok(())
+    }
+
+    /// Assigns yet unallocated fractions of a token to a holder.
+    pub fn allocate(&mut self, holder: H, nft: OwnedNft) -> Result<(), LedgerError> {
+        let total = self.total(nft.token_no)?;
+        if nft.fractions == TokenFractions::ZERO {
+            return Err(LedgerError::ZeroAllocation(nft.token_no));
+        }
+        let allocated = self.allocated(nft.token_no)?;
+        if allocated.value() as u128 + nft.fractions.value() as u128 > total.value() as u128 {
+            return Err(LedgerError::OverAllocation {
+                token: nft.token_no,
+                total,
+                allocated: allocated.value() as u128 + nft.fractions.value() as u128,
+            });
+        }
+        let balance = self
+            .holdings
+            .get_mut(&nft.token_no)
+            .expect("holdings are created on registration")
+            .entry(holder)
+            .or_default();
+        *balance = balance.saturating_add(nft.fractions);
+        Ok(())
+    }
+
+    /// Moves fractions of a token from one holder to another.
+    pub fn transfer(&mut self, from: &H, to: H, nft: OwnedNft) -> Result<(), LedgerError> {
+        self.total(nft.token_no)?;
+        if nft.fractions == TokenFractions::ZERO {
+            return Err(LedgerError::ZeroAllocation(nft.token_no));
+        }
+        let holdings = self
+            .holdings
+            .get_mut(&nft.token_no)
+            .expect("holdings are created on registration");
+        let available = holdings.get(from).copied().unwrap_or_default();
+        let Some(rest) = available.checked_sub(nft.fractions) else {
+            return Err(LedgerError::InsufficientFractions {
+                token: nft.token_no,
+                available,
+                requested: nft.fractions,
+            });
+        };
+        if rest == TokenFractions::ZERO {
+            holdings.remove(from);
+        } else {
+            holdings.insert(from.clone(), rest);
+        }
+        let balance = holdings.entry(to).or_default();
+        *balance = balance.saturating_add(nft.fractions);
+        Ok(())
+    }
+
+    /// Total number of fractions of a token.
+    pub fn total(&self, token: TokenNo) -> Result<TokenFractions, LedgerError> {
+        self.totals
+            .get(&token)
+            .copied()
+            .ok_or(LedgerError::UnknownToken(token))
+    }
+
+    /// Number of fractions of a token allocated to all holders.
+    pub fn allocated(&self, token: TokenNo) -> Result<TokenFractions, LedgerError> {
+        let holdings = self
+            .holdings
+            .get(&token)
+            .ok_or(LedgerError::UnknownToken(token))?;
+        Ok(holdings
+            .values()
+            .fold(TokenFractions::ZERO, |sum, fractions| sum.saturating_add(*fractions)))
+    }
+
+    /// Number of fractions of a token which are not allocated to any holder.
+    pub fn unallocated(&self, token: TokenNo) -> Result<TokenFractions, LedgerError> {
+        Ok(self.total(token)?.saturating_sub(self.allocated(token)?))
+    }
+
+    /// Checks that all fractions of all tokens are allocated.
+    pub fn check_complete(&self) -> Result<(), LedgerError> {
+        for (token, total) in &self.totals {
+            let allocated = self.allocated(*token)?;
+            if allocated != *total {
+                return Err(LedgerError::UnderAllocation { token: *token, total: *total, allocated });
+            }
+        }
+        Ok(())
+    }
+
+    /// Tokens known to the ledger.
+    pub fn tokens(&self) -> impl Iterator<Item = TokenNo> + '_ { self.totals.keys().copied() }
+
+    /// Number of fractions of a token owned by a holder.
+    pub fn balance(&self, holder: &H, token: TokenNo) -> TokenFractions {
+        self.holdings
+            .get(&token)
+            .and_then(|holdings| holdings.get(holder))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Lists holders of a token with their fractions.
+    pub fn holders(&self, token: TokenNo) -> impl Iterator<Item = (&H, TokenFractions)> + '_ {
+        self.holdings.get(&token).into_iter().flat_map(|holdings| {
+            holdings
+                .iter()
+                .map(|(holder, fractions)| (holder, *fractions))
+        })
+    }
+
+    /// Lists allocations owned by a holder.
+    pub fn owned_by<'a>(&'a self, holder: &'a H) -> impl Iterator<Item = OwnedNft> + 'a {
+        self.holdings.iter().filter_map(move |(token, holdings)| {
+            holdings
+                .get(holder)
+                .map(|fractions| OwnedNft { token_no: *token, fractions: *fractions })
+        })
+    }
+
+    /// Share of a token owned by a holder, in percents.
+    pub fn percentage(&self, holder: &H, token: TokenNo) -> Result<f64, LedgerError> {
+        let total = self.total(token)?;
+        Ok(self.balance(holder, token).value() as f64 * 100.0 / total.value() as f64)
+    }
+
+    /// Shares of a token owned by each of its holders, in percents.
+    pub fn ownership(&self, token: TokenNo) -> Result<Vec<(&H, f64)>, LedgerError> {
+        let total = self.total(token)?.value() as f64;
+        Ok(self
+            .holders(token)
+            .map(|(holder, fractions)| (holder, fractions.value() as f64 * 100.0 / total))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn nft(no: u32, fractions: u64) -> OwnedNft { OwnedNft::new(no, fractions) }
+
+    fn ledger() -> FractionLedger<&'static str> {
+        FractionLedger::with([(1.into(), 100.into()), (2.into(), 1.into())], [
+            ("alice", nft(1, 60)),
+            ("bob", nft(1, 40)),
+            ("alice", nft(2, 1)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn allocation() {
+        let ledger = ledger();
+        assert_eq!(ledger.balance(&"alice", 1.into()), 60.into());
+        assert_eq!(ledger.percentage(&"bob", 1.into()), Ok(40.0));
+        assert_eq!(ledger.ownership(2.into()), Ok(vec![(&"alice", 100.0)]));
+        assert_eq!(ledger.owned_by(&"alice").collect::<Vec<_>>(), vec![nft(1, 60), nft(2, 1)]);
+
+        assert_eq!(
+            FractionLedger::with([(1.into(), 100.into())], [("alice", nft(1, 60)), ("bob", nft(1, 41))]),
+            Err(LedgerError::OverAllocation { token: 1.into(), total: 100.into(), allocated: 101 })
+        );
+        assert_eq!(
+            FractionLedger::with([(1.into(), 100.into())], [("alice", nft(1, 60))]),
+            Err(LedgerError::UnderAllocation { token: 1.into(), total: 100.into(), allocated: 60.into() })
+        );
+        assert_eq!(
+            FractionLedger::with([(1.into(), 100.into())], [("alice", nft(3, 1))]),
+            Err(LedgerError::UnknownToken(3.into()))
+        );
+        assert_eq!(
+            FractionLedger::<&str>::with([(1.into(), 1.into()), (1.into(), 1.into())], []),
+            Err(LedgerError::DuplicateToken(1.into()))
+        );
+    }
+
+    #[test]
+    fn transfers() {
+        let mut ledger = ledger();
+        ledger.transfer(&"alice", "carol", nft(1, 10)).unwrap();
+        ledger.transfer(&"bob", "carol", nft(1, 40)).unwrap();
+        assert_eq!(ledger.balance(&"carol", 1.into()), 50.into());
+        assert_eq!(ledger.holders(1.into()).count(), 2);
+        assert_eq!(
+            ledger.transfer(&"bob", "carol", nft(1, 1)),
+            Err(LedgerError::InsufficientFractions { token: 1.into(), available: 0.into(), requested: 1.into() })
+        );
+        assert_eq!(ledger.transfer(&"alice", "bob", nft(1, 0)), Err(LedgerError::ZeroAllocation(1.into())));
+        assert_eq!(ledger.transfer(&"alice", "bob", nft(5, 1)), Err(LedgerError::UnknownToken(5.into())));
+        assert_eq!(ledger.check_complete(), Ok(()));
+        assert_eq!(ledger.unallocated(1.into()), Ok(TokenFractions::ZERO));
+    }
+}
@@ -33,12 +33,65 @@ mod nft;
 mod types;
 mod names;
 mod por;
+mod attachment;
+mod cid;
+mod chunks;
+mod collection;
+mod locator;
+mod store;
+mod attributes;
+mod ordinals;
+mod tokens;
+mod ledger;
+mod royalty;
+mod engraving;
+mod sft;
+mod intl;
+mod text;
+mod terms;
+mod ticker_policy;
+#[cfg(feature = "serde")]
+mod erc721;
 
+pub use attachment::{AttachmentError, AttachmentV1, DigestAlgo, ATTACHMENT_DIGEST_TAG};
+#[cfg(feature = "serde")]
+pub use attributes::AttrJsonError;
+pub use attributes::{AttrDecimal, AttrValue, DisplayHint, NftAttribute, NftAttributes, RarityReport};
+pub use chunks::{
+    ChunkError, ChunkProof, ChunkedMedia, MEDIA_CHUNK_LEAF_TAG, MEDIA_CHUNK_MAX_SIZE, MEDIA_CHUNK_NODE_TAG,
+};
+pub use cid::{
+    Cid, CidError, CidVersion, Multibase, MULTICODEC_DAG_PB, MULTICODEC_RAW, MULTIHASH_BLAKE3, MULTIHASH_SHA2_256,
+};
+pub use collection::{CollectionError, NftCollection};
+pub use engraving::{Engraving, EngravingError, EngravingHistory, EngravingText};
+#[cfg(feature = "serde")]
+pub use erc721::{media_type_by_extension, Erc721Error, Erc721Field, Erc721Import, Erc721Loss, Erc721Metadata};
 pub use fungible::*;
+pub use intl::{IntlAssetName, IntlName, IntlNameError, NameScript, INTL_NAME_MAX_LEN};
+pub use ledger::{FractionLedger, LedgerError};
+pub use locator::{LocatorError, LocatorFailure, LocatorStr, MediaFetcher, MediaLocator, MediaLocators, ResolveError};
 pub use names::{AssetName, Details, Ticker};
 pub use nft::*;
+pub use ordinals::{Inscription, InscriptionError, InscriptionMedia, ORD_PROTOCOL_ID};
 pub use por::*;
-pub use types::{rgb21_stl, rgb_contract_stl, CommonTypes, Rgb21Types, LIB_ID_RGB21, LIB_ID_RGB_INTERFACES};
+pub use royalty::{
+    BasisPoints, Beneficiary, Royalty, RoyaltyError, RoyaltyPolicy, RoyaltyRecipient, RoyaltyShare, BASIS_POINTS_FULL,
+};
+pub use sft::{ClassId, SftAllocation, SftBalances, SftClass, SftClasses, SftError, SftParseError};
+pub use store::{MediaStore, StoreError};
+pub use terms::{ContractTerms, TermsDocument, TermsError, CONTRACT_TERMS_TAG};
+pub use text::{ContractText, TextError, TextMarkup, CONTRACT_TEXT_TAG};
+pub use ticker_policy::{
+    ReservedCategory, TickerAssessment, TickerFinding, TickerPolicy, TickerRisk, CRYPTO_TICKERS, ISO_4217_CODES,
+    TICKER_CONFUSABLES,
+};
+pub use tokens::{TokenRange, TokenSet, TokenSetError, TOKEN_SET_MAX_ALLOCATIONS};
+pub use types::{
+    rgb21_stl, rgb_contract_stl, rgb_sft_stl, CommonTypes, Rgb21Types, SftTypes, LIB_ID_RGB21, LIB_ID_RGB_INTERFACES,
+    LIB_ID_RGB_SFT,
+};
 
 pub const LIB_NAME_RGB_CONTRACT: &str = "RGBContract";
 pub const LIB_NAME_RGB21: &str = "RGB21";
+pub const LIB_NAME_RGB_SFT: &str = "RGBSemiFungible";
@@ -0,0 +1,387 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::confinement::TinyVec;
+use strict_encoding::stl::AsciiPrintable;
+use strict_encoding::{InvalidRString, RString, StrictDeserialize, StrictSerialize};
+
+use crate::{Attachment, AttachmentError, Cid, CidError, LIB_NAME_RGB21};
+
+/// URI string used by media locators.
+#[derive(Wrapper, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, From)]
+#[wrapper(Deref, Display, FromStr)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct LocatorStr(RString<AsciiPrintable, AsciiPrintable, 1, 0x800>);
+
+impl_ident_type!(LocatorStr);
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum LocatorError {
+    /// invalid locator string; {0}
+    #[from]
+    InvalidString(InvalidRString),
+
+    /// locator '{0}' contains whitespace.
+    Whitespace(String),
+
+    /// locator '{0}' has no valid URI scheme.
+    NoScheme(String),
+
+    /// '{0}' is not an HTTPS URL.
+    NotHttps(String),
+
+    /// HTTPS URL '{0}' has no host.
+    NoHost(String),
+
+    /// invalid IPFS content identifier; {0}
+    #[from]
+    InvalidCid(CidError),
+
+    /// '{0}' is not a magnet link with an exact topic (`xt`) parameter.
+    InvalidMagnet(String),
+}
+
+/// Location from which external media can be retrieved.
+///
+/// The locator doesn't need to be trusted: the data fetched from any location must be verified
+/// against the attachment digest (see [`MediaLocators::resolve`]).
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21, tags = custom, dumb = Self::Uri(strict_dumb!()))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum MediaLocator {
+    /// HTTPS URL, including the `https://` scheme.
+    #[strict_type(tag = 0x01)]
+    Https(LocatorStr),
+
+    /// IPFS content identifier, without the `ipfs://` scheme. Any CID version and codec is allowed,
+    /// since the data are verified after the retrieval.
+    #[strict_type(tag = 0x02)]
+    Ipfs(LocatorStr),
+
+    /// Magnet link, including the `magnet:` scheme.
+    #[strict_type(tag = 0x03)]
+    Magnet(LocatorStr),
+
+    /// Any other URI, including its scheme.
+    #[strict_type(tag = 0xFF)]
+    Uri(LocatorStr),
+}
+
+impl MediaLocator {
+    pub fn https(url: &str) -> Result<Self, LocatorError> {
+        let locator = MediaLocator::Https(LocatorStr::from_str(url)?);
+        locator.validate()?;
+        Ok(locator)
+    }
+
+    pub fn ipfs(cid: &Cid) -> Self {
+        MediaLocator::Ipfs(LocatorStr::from_str(&cid.to_string()).expect("CID is always a valid locator"))
+    }
+
+    pub fn magnet(link: &str) -> Result<Self, LocatorError> {
+        let locator = MediaLocator::Magnet(LocatorStr::from_str(link)?);
+        locator.validate()?;
+        Ok(locator)
+    }
+
+    pub fn uri(uri: &str) -> Result<Self, LocatorError> {
+        let locator = MediaLocator::Uri(LocatorStr::from_str(uri)?);
+        locator.validate()?;
+        Ok(locator)
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            MediaLocator::Https(s) | MediaLocator::Ipfs(s) | MediaLocator::Magnet(s) | MediaLocator::Uri(s) => {
+                s.as_str()
+            }
+        }
+    }
+
+    /// Parses IPFS content identifier, if the locator is an IPFS one.
+    pub fn cid(&self) -> Option<Result<Cid, CidError>> {
+        match self {
+            MediaLocator::Ipfs(s) => Some(Cid::from_str(s.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Checks that the locator string matches the requirements of its kind.
+    ///
+    /// Strict decoding doesn't perform these checks, thus locators read from a contract must be
+    /// validated before use.
+    pub fn validate(&self) -> Result<(), LocatorError> {
+        let s = self.as_str();
+        if s.contains(char::is_whitespace) {
+            return Err(LocatorError::Whitespace(s.to_owned()));
+        }
+        match self {
+            MediaLocator::Https(_) => {
+                let rest = s
+                    .strip_prefix("https://")
+                    .ok_or_else(|| LocatorError::NotHttps(s.to_owned()))?;
+                let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+                let host = authority.rsplit('@').next().unwrap_or_default();
+                if host.is_empty() || host.starts_with(':') {
+                    return Err(LocatorError::NoHost(s.to_owned()));
+                }
+            }
+            MediaLocator::Ipfs(_) => {
+                Cid::from_str(s)?;
+            }
+            MediaLocator::Magnet(_) => {
+                let query = s
+                    .strip_prefix("magnet:?")
+                    .ok_or_else(|| LocatorError::InvalidMagnet(s.to_owned()))?;
+                if !query
+                    .split('&')
+                    .any(|param| param.starts_with("xt=") && param.len() > 3)
+                {
+                    return Err(LocatorError::InvalidMagnet(s.to_owned()));
+                }
+            }
+            MediaLocator::Uri(_) => {
+                let scheme = s
+                    .split_once(':')
+                    .map(|(scheme, _)| scheme)
+                    .ok_or_else(|| LocatorError::NoScheme(s.to_owned()))?;
+                let mut chars = scheme.chars();
+                if !chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+                    || !chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                {
+                    return Err(LocatorError::NoScheme(s.to_owned()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats the locator as a URI, using `ipfs://` scheme for IPFS locators.
+impl Display for MediaLocator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaLocator::Ipfs(cid) => write!(f, "ipfs://{cid}"),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// Parses URI into the locator of a matching kind and validates it.
+impl FromStr for MediaLocator {
+    type Err = LocatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let locator = if s.starts_with("https://") {
+            MediaLocator::Https(LocatorStr::from_str(s)?)
+        } else if let Some(cid) = s.strip_prefix("ipfs://") {
+            MediaLocator::Ipfs(LocatorStr::from_str(cid)?)
+        } else if s.starts_with("magnet:") {
+            MediaLocator::Magnet(LocatorStr::from_str(s)?)
+        } else {
+            MediaLocator::Uri(LocatorStr::from_str(s)?)
+        };
+        locator.validate()?;
+        Ok(locator)
+    }
+}
+
+/// Ordered list of locations for the external media, starting from the most preferred one.
+#[derive(Wrapper, WrapperMut, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct MediaLocators(TinyVec<MediaLocator>);
+
+impl StrictSerialize for MediaLocators {}
+impl StrictDeserialize for MediaLocators {}
+
+/// Source of the media data for a given locator, like an HTTP client or an IPFS node.
+pub trait MediaFetcher {
+    type Error: Error;
+
+    fn fetch(&mut self, locator: &MediaLocator) -> Result<Vec<u8>, Self::Error>;
+}
+
+#[derive(Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum LocatorFailure<E: Error> {
+    /// invalid locator; {0}
+    Invalid(LocatorError),
+
+    /// unable to fetch the media; {0}
+    Fetch(E),
+
+    /// fetched media doesn't match the attachment; {0}
+    Mismatch(AttachmentError),
+}
+
+/// Error returned when none of the media locators has provided valid data.
+#[derive(Debug)]
+pub struct ResolveError<E: Error> {
+    /// Failures for each of the locators, in the order they were tried.
+    pub failures: Vec<(MediaLocator, LocatorFailure<E>)>,
+}
+
+impl<E: Error> Display for ResolveError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "external media is not available from any of {} location(s)", self.failures.len())
+    }
+}
+
+impl<E: Error> Error for ResolveError<E> {}
+
+impl MediaLocators {
+    pub fn with(locators: impl IntoIterator<Item = MediaLocator>) -> Result<Self, amplify::confinement::Error> {
+        TinyVec::try_from_iter(locators).map(Self)
+    }
+
+    /// Validates all the locators in the list.
+    pub fn validate(&self) -> Result<(), LocatorError> { self.iter().try_for_each(MediaLocator::validate) }
+
+    /// Tries locators one by one, returning the first one which provides data matching the
+    /// attachment digest, together with the data.
+    pub fn resolve<F: MediaFetcher>(
+        &self,
+        attachment: &Attachment,
+        fetcher: &mut F,
+    ) -> Result<(MediaLocator, Vec<u8>), ResolveError<F::Error>> {
+        let mut failures = vec![];
+        for locator in self.iter() {
+            if let Err(err) = locator.validate() {
+                failures.push((locator.clone(), LocatorFailure::Invalid(err)));
+                continue;
+            }
+            let data = match fetcher.fetch(locator) {
+                Ok(data) => data,
+                Err(err) => {
+                    failures.push((locator.clone(), LocatorFailure::Fetch(err)));
+                    continue;
+                }
+            };
+            match attachment.verify(&data) {
+                Ok(()) => return Ok((locator.clone(), data)),
+                Err(err) => failures.push((locator.clone(), LocatorFailure::Mismatch(err))),
+            }
+        }
+        Err(ResolveError { failures })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::MediaType;
+
+    #[derive(Debug, Display, Error)]
+    #[display("not found")]
+    struct NotFound;
+
+    struct LocalFetcher(BTreeMap<String, Vec<u8>>);
+
+    impl MediaFetcher for LocalFetcher {
+        type Error = NotFound;
+
+        fn fetch(&mut self, locator: &MediaLocator) -> Result<Vec<u8>, Self::Error> {
+            self.0.get(&locator.to_string()).cloned().ok_or(NotFound)
+        }
+    }
+
+    #[test]
+    fn parse_display() {
+        for s in [
+            "https://example.com/nft/1.png",
+            "ipfs://bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e",
+            "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=nft.png",
+            "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U",
+        ] {
+            let locator = MediaLocator::from_str(s).unwrap();
+            assert_eq!(locator.to_string(), s);
+        }
+        assert!(matches!(MediaLocator::from_str("https://example.com").unwrap(), MediaLocator::Https(_)));
+        assert!(matches!(
+            MediaLocator::from_str("ipfs://bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e").unwrap(),
+            MediaLocator::Ipfs(_)
+        ));
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(MediaLocator::https("http://example.com"), Err(LocatorError::NotHttps(s!("http://example.com"))));
+        assert_eq!(MediaLocator::https("https:///path"), Err(LocatorError::NoHost(s!("https:///path"))));
+        assert_eq!(
+            MediaLocator::https("https://example.com/a b"),
+            Err(LocatorError::Whitespace(s!("https://example.com/a b")))
+        );
+        assert!(matches!(MediaLocator::from_str("ipfs://bafkrei1"), Err(LocatorError::InvalidCid(_))));
+        assert_eq!(MediaLocator::magnet("magnet:?dn=file"), Err(LocatorError::InvalidMagnet(s!("magnet:?dn=file"))));
+        assert_eq!(MediaLocator::uri("no-scheme"), Err(LocatorError::NoScheme(s!("no-scheme"))));
+        assert_eq!(MediaLocator::uri("1ab:xyz"), Err(LocatorError::NoScheme(s!("1ab:xyz"))));
+    }
+
+    #[test]
+    fn resolve() {
+        let attachment = Attachment::with_data(MediaType::with("image/png"), b"genuine");
+        let locators = MediaLocators::with([
+            MediaLocator::Https(LocatorStr::from("http://insecure.com")),
+            MediaLocator::https("https://missing.com/1.png").unwrap(),
+            MediaLocator::https("https://forged.com/1.png").unwrap(),
+            MediaLocator::ipfs(&attachment.to_cid()),
+        ])
+        .unwrap();
+
+        let mut fetcher = LocalFetcher(bmap! {
+            s!("https://forged.com/1.png") => b"forged".to_vec(),
+            format!("ipfs://{}", attachment.to_cid()) => b"genuine".to_vec()
+        });
+        let (locator, data) = locators.resolve(&attachment, &mut fetcher).unwrap();
+        assert_eq!(locator, MediaLocator::ipfs(&attachment.to_cid()));
+        assert_eq!(data, b"genuine");
+
+        fetcher.0.clear();
+        let err = locators.resolve(&attachment, &mut fetcher).unwrap_err();
+        assert_eq!(err.failures.len(), 4);
+        assert!(matches!(err.failures[0].1, LocatorFailure::Invalid(LocatorError::NotHttps(_))));
+        assert!(matches!(err.failures[1].1, LocatorFailure::Fetch(NotFound)));
+    }
+
+    #[test]
+    fn strict_encoding() {
+        let locators = MediaLocators::with([
+            MediaLocator::https("https://example.com/nft.png").unwrap(),
+            MediaLocator::magnet("magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a").unwrap(),
+        ])
+        .unwrap();
+        let data = locators.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(MediaLocators::from_strict_serialized::<0xFFFF>(data).unwrap(), locators);
+    }
+}
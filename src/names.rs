@@ -18,6 +18,7 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
+use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
@@ -27,7 +28,11 @@ use strict_types::StrictVal;
 
 use crate::LIB_NAME_RGB_CONTRACT;
 
-#[derive(Wrapper, Clone, Ord, PartialOrd, Eq, From)]
+/// Asset ticker.
+///
+/// Tickers are compared, ordered and hashed case-insensitively, so `usdt` and `USDT` are the same
+/// ticker; the original casing chosen by the issuer is preserved for display purposes.
+#[derive(Wrapper, Clone, Eq, From)]
 #[wrapper(Deref, Display, FromStr)]
 #[derive(StrictDumb, StrictType, StrictEncode, StrictDecode)]
 #[strict_type(lib = LIB_NAME_RGB_CONTRACT, dumb = Self::from(RString::strict_dumb()))]
@@ -35,15 +40,23 @@ use crate::LIB_NAME_RGB_CONTRACT;
 pub struct Ticker(RString<Alpha, AlphaNum, 2, 8>);
 
 impl PartialEq for Ticker {
-    fn eq(&self, other: &Self) -> bool {
-        self.as_str()
-            .to_uppercase()
-            .eq(&other.as_str().to_uppercase())
+    fn eq(&self, other: &Self) -> bool { self.as_str().eq_ignore_ascii_case(other.as_str()) }
+}
+
+impl PartialOrd for Ticker {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Ticker {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.as_str().bytes().map(|b| b.to_ascii_uppercase());
+        let rhs = other.as_str().bytes().map(|b| b.to_ascii_uppercase());
+        lhs.cmp(rhs)
     }
 }
 
 impl Hash for Ticker {
-    fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().to_uppercase().hash(state) }
+    fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().to_ascii_uppercase().hash(state) }
 }
 
 impl_ident_type!(Ticker);
@@ -51,6 +64,20 @@ impl_ident_subtype!(Ticker);
 
 impl Ticker {
     pub fn from_strict_val_unchecked(value: &StrictVal) -> Self { Self::from_str(&value.unwrap_string()).unwrap() }
+
+    /// Ticker with the casing chosen by the issuer, which should be used for display.
+    pub fn as_issued(&self) -> &str { self.as_str() }
+
+    /// Canonical uppercase form of the ticker.
+    pub fn to_canonical(&self) -> Ticker {
+        Ticker::from_str(&self.as_str().to_ascii_uppercase()).expect("uppercase form of a ticker is a valid ticker")
+    }
+
+    /// Checks whether the ticker is in its canonical uppercase form.
+    pub fn is_canonical(&self) -> bool { !self.as_str().bytes().any(|b| b.is_ascii_lowercase()) }
+
+    /// Compares tickers taking their casing into account.
+    pub fn eq_exact(&self, other: &Ticker) -> bool { self.as_str() == other.as_str() }
 }
 
 #[derive(Wrapper, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, From)]
@@ -73,3 +100,40 @@ impl AssetName {
 #[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
 pub struct Details(RString<AsciiPrintable, AsciiPrintable, 1, 0xFF>);
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+
+    fn ticker(s: &str) -> Ticker { Ticker::from_str(s).unwrap() }
+
+    #[test]
+    fn ticker_case_insensitivity() {
+        assert_eq!(ticker("usdt"), ticker("USDT"));
+        assert_eq!(ticker("usdt").cmp(&ticker("USDT")), Ordering::Equal);
+        assert!(ticker("abc") < ticker("ABD"));
+        assert!(ticker("Zed") > ticker("abc"));
+        assert!(ticker("AB") < ticker("ab1"));
+        assert!(!ticker("usdt").eq_exact(&ticker("USDT")));
+
+        let btree = BTreeSet::from([ticker("usdt"), ticker("USDT"), ticker("UsDt"), ticker("BTC")]);
+        assert_eq!(btree.len(), 2);
+        assert!(btree.contains(&ticker("Usdt")));
+        let hash = HashSet::from([ticker("usdt"), ticker("USDT"), ticker("UsDt"), ticker("BTC")]);
+        assert_eq!(hash.len(), 2);
+        assert!(hash.contains(&ticker("btc")));
+    }
+
+    #[test]
+    fn ticker_canonical_form() {
+        let issued = ticker("tUSDt");
+        assert_eq!(issued.as_issued(), "tUSDt");
+        assert_eq!(issued.to_string(), "tUSDt");
+        assert_eq!(issued.to_canonical().as_str(), "TUSDT");
+        assert!(!issued.is_canonical());
+        assert!(issued.to_canonical().is_canonical());
+        assert_eq!(issued.to_canonical(), issued);
+    }
+}
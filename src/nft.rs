@@ -20,8 +20,8 @@
 
 #![allow(unused_braces)]
 
-use std::fmt;
-use std::fmt::Debug;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::str::FromStr;
 
 use amplify::confinement::SmallBlob;
@@ -296,6 +296,9 @@ impl EmbeddedMedia {
 
         Self { mime, data }
     }
+
+    /// Constructs attachment referencing the embedded data.
+    pub fn attachment(&self) -> Attachment { Attachment::with_data(self.mime.clone(), &self.data) }
 }
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -372,27 +375,132 @@ pub enum NftParseError {
     #[display(doc_comments)]
     /// allocation must have format <fraction>@<token_index>.
     WrongFormat,
+
+    #[display(doc_comments)]
+    /// invalid allocation '{item}' at position {pos}; {error}
+    InvalidItem {
+        item: String,
+        pos: usize,
+        error: Box<NftParseError>,
+    },
+
+    #[display(doc_comments)]
+    /// token {token} at position {pos} is already allocated in the list.
+    DuplicateToken { token: TokenNo, pos: usize },
+
+    #[display(doc_comments)]
+    /// allocation list is empty.
+    Empty,
+}
+
+/// Parses a single allocation in `<fraction>@<token_index>` or `@<token_index>` form.
+fn parse_allocation(s: &str) -> Result<(TokenNo, TokenFractions), NftParseError> {
+    let (fraction, token_index) = s.split_once('@').ok_or(NftParseError::WrongFormat)?;
+    let token_no = token_index
+        .parse()
+        .map_err(|_| NftParseError::InvalidIndex(token_index.to_owned()))?;
+    let fractions = if fraction.is_empty() {
+        TokenFractions::from(1)
+    } else {
+        fraction
+            .parse()
+            .map_err(|_| NftParseError::InvalidFraction(fraction.to_lowercase()))?
+    };
+    Ok((token_no, fractions))
+}
+
+/// Formats the allocation in the canonical `<fraction>@<token_index>` form.
+impl Display for Nft {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}@{}", self.fractions, self.token_no) }
 }
 
+/// Parses allocation in the `<fraction>@<token_index>` form; `@<token_index>` is a shorthand for a
+/// single fraction of the token (i.e. the whole non-divisible token).
+impl FromStr for Nft {
+    type Err = NftParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_allocation(s).map(|(token_no, fractions)| Nft { token_no, fractions })
+    }
+}
+
+/// Formats the allocation in the canonical `<fraction>@<token_index>` form.
+impl Display for OwnedNft {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}@{}", self.fractions, self.token_no) }
+}
+
+/// Parses allocation in the `<fraction>@<token_index>` form; `@<token_index>` is a shorthand for a
+/// single fraction of the token (i.e. the whole non-divisible token).
 impl FromStr for OwnedNft {
     type Err = NftParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if !s.contains('@') {
-            return Err(NftParseError::WrongFormat);
+        parse_allocation(s).map(|(token_no, fractions)| OwnedNft { token_no, fractions })
+    }
+}
+
+/// List of NFT allocations with at most one allocation per token.
+///
+/// Textual form is a comma-separated list of allocations, like `1@5,1@6` or `@5,@6`, without
+/// whitespace. It uses only characters which are allowed in URI query, so it can be put into
+/// invoices without escaping. The canonical form lists allocations in the token index order and
+/// doesn't use the whole-token shorthand.
+#[derive(Wrapper, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+pub struct NftAllocations(BTreeMap<TokenNo, TokenFractions>);
+
+impl NftAllocations {
+    /// Constructs allocation list, failing on a duplicated token.
+    pub fn with(allocations: impl IntoIterator<Item = OwnedNft>) -> Result<Self, TokenNo> {
+        let mut map = BTreeMap::new();
+        for nft in allocations {
+            if map.insert(nft.token_no, nft.fractions).is_some() {
+                return Err(nft.token_no);
+            }
         }
+        Ok(Self(map))
+    }
+
+    pub fn allocations(&self) -> impl Iterator<Item = OwnedNft> + '_ {
+        self.0
+            .iter()
+            .map(|(token_no, fractions)| OwnedNft { token_no: *token_no, fractions: *fractions })
+    }
+}
 
-        match s.split_once('@') {
-            Some((fraction, token_index)) => Ok(OwnedNft {
-                token_no: token_index
-                    .parse()
-                    .map_err(|_| NftParseError::InvalidIndex(token_index.to_owned()))?,
-                fractions: fraction
-                    .parse()
-                    .map_err(|_| NftParseError::InvalidFraction(fraction.to_lowercase()))?,
-            }),
-            None => Err(NftParseError::WrongFormat),
+impl Display for NftAllocations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, nft) in self.allocations().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            Display::fmt(&nft, f)?;
         }
+        Ok(())
+    }
+}
+
+impl FromStr for NftAllocations {
+    type Err = NftParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(NftParseError::Empty);
+        }
+        let mut map = BTreeMap::new();
+        let mut pos = 0;
+        for item in s.split(',') {
+            let (token, fractions) = parse_allocation(item).map_err(|error| NftParseError::InvalidItem {
+                item: item.to_owned(),
+                pos,
+                error: Box::new(error),
+            })?;
+            if map.insert(token, fractions).is_some() {
+                return Err(NftParseError::DuplicateToken { token, pos });
+            }
+            pos += item.len() + 1;
+        }
+        Ok(Self(map))
     }
 }
 
@@ -431,6 +539,14 @@ impl NftSpec {
 
         Self { name, embedded, external, reserves }
     }
+
+    /// Lists attachments for all media referenced by the specification, starting with the
+    /// embedded one.
+    pub fn attachments(&self) -> Vec<Attachment> {
+        let mut attachments = vec![self.embedded.attachment()];
+        attachments.extend(self.external.clone());
+        attachments
+    }
 }
 
 #[cfg(test)]
@@ -439,6 +555,51 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn allocation_notation() {
+        assert_eq!(Nft::from_str("3@5").unwrap(), Nft::new(5, 3));
+        assert_eq!(OwnedNft::from_str("@5").unwrap(), OwnedNft::new(5, 1));
+        assert_eq!(OwnedNft::new(5, 1).to_string(), "1@5");
+        assert_eq!(Nft::new(0, 100).to_string(), "100@0");
+
+        assert_eq!(Nft::from_str("5"), Err(NftParseError::WrongFormat));
+        assert_eq!(Nft::from_str("x@5"), Err(NftParseError::InvalidFraction(s!("x"))));
+        assert_eq!(Nft::from_str("1@4294967296"), Err(NftParseError::InvalidIndex(s!("4294967296"))));
+        // Non-canonical forms are accepted and printed canonically
+        assert_eq!(OwnedNft::from_str("0@5").unwrap(), OwnedNft::new(5, 0));
+        assert_eq!(OwnedNft::from_str("01@05").unwrap().to_string(), "1@5");
+    }
+
+    #[test]
+    fn allocation_list() {
+        let list = NftAllocations::from_str("1@6,@5,10@7").unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.to_string(), "1@5,1@6,10@7");
+        assert_eq!(NftAllocations::from_str(&list.to_string()).unwrap(), list);
+
+        assert_eq!(NftAllocations::from_str(""), Err(NftParseError::Empty));
+        assert_eq!(
+            NftAllocations::from_str("1@5,"),
+            Err(NftParseError::InvalidItem {
+                item: s!(""),
+                pos: 4,
+                error: Box::new(NftParseError::WrongFormat)
+            })
+        );
+        assert_eq!(
+            NftAllocations::from_str("1@5,2@6,1@5"),
+            Err(NftParseError::DuplicateToken { token: 5.into(), pos: 8 })
+        );
+        let err = NftAllocations::from_str("1@5,x@6").unwrap_err();
+        assert_eq!(err, NftParseError::InvalidItem {
+            item: s!("x@6"),
+            pos: 4,
+            error: Box::new(NftParseError::InvalidFraction(s!("x")))
+        });
+        assert_eq!(err.to_string(), "invalid allocation 'x@6' at position 4; invalid fraction x.");
+        assert_eq!(NftAllocations::with([OwnedNft::new(1, 1), OwnedNft::new(1, 2)]), Err(1.into()));
+    }
+
     #[test]
     fn owned_fraction_from_str() {
         let owned_fraction = match TokenFractions::from_str("1") {
@@ -0,0 +1,381 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Import of Bitcoin Ordinals inscriptions.
+//!
+//! Inscription is stored in a taproot script-path spending script inside an envelope:
+//!
+//! ```text
+//! OP_FALSE OP_IF
+//!   OP_PUSH "ord"
+//!   OP_PUSH 1 OP_PUSH <content type>
+//!   ... other fields ...
+//!   OP_0
+//!   OP_PUSH <body chunk> ...
+//! OP_ENDIF
+//! ```
+//!
+//! Fields are tag-value push pairs; an empty push separates them from the body, which may be split
+//! across multiple pushes. Following the `ord` reference implementation, `OP_1`..`OP_16` and
+//! `OP_1NEGATE` inside the envelope are treated as single-byte pushes.
+
+use std::str::FromStr;
+
+use amplify::confinement::SmallBlob;
+use bc::{TapCode, TapScript};
+
+use crate::{Attachment, EmbeddedMedia, MediaRegName, MediaType};
+
+/// Protocol identifier of the Ordinals envelope.
+pub const ORD_PROTOCOL_ID: &[u8] = b"ord";
+
+const TAG_CONTENT_TYPE: u8 = 1;
+const TAG_CONTENT_ENCODING: u8 = 9;
+const TAG_DELEGATE: u8 = 11;
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum InscriptionError {
+    /// script doesn't contain an inscription envelope.
+    NoEnvelope,
+
+    /// push at byte {pos} exceeds the script length.
+    Truncated { pos: usize },
+
+    /// envelope started at byte {start} is not terminated with OP_ENDIF.
+    Unterminated { start: usize },
+
+    /// non-push opcode {opcode:#04x} at byte {pos} inside the envelope.
+    UnexpectedOpcode { pos: usize, opcode: u8 },
+
+    /// field tag at byte {pos} has no value.
+    IncompleteField { pos: usize },
+
+    /// field {tag} at byte {pos} is repeated.
+    DuplicateField { tag: u8, pos: usize },
+
+    /// unknown even field {tag} at byte {pos}, which makes the inscription invalid.
+    UnknownEvenField { tag: u8, pos: usize },
+
+    /// invalid content type '{content_type}' at byte {pos}.
+    ContentType { content_type: String, pos: usize },
+
+    /// inscription body is encoded with '{0}', which is not supported.
+    ContentEncoding(String),
+
+    /// inscription delegates its content to another inscription, so it has no own content.
+    Delegated,
+}
+
+/// Inscription extracted from an Ordinals envelope.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Inscription {
+    /// Inscription content type; `application/octet-stream` if the envelope doesn't specify it.
+    pub mime: MediaType,
+    pub body: Vec<u8>,
+}
+
+/// Media representation of an inscription.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, From)]
+pub enum InscriptionMedia {
+    /// Inscription body fits into the embedded media size limit.
+    #[from]
+    Embedded(EmbeddedMedia),
+
+    /// Inscription body is too large to be embedded; the data must be distributed separately.
+    #[from]
+    Attachment(Attachment),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+enum Instr<'script> {
+    Push(&'script [u8]),
+    Op(u8),
+}
+
+struct Instructions<'script> {
+    script: &'script [u8],
+    pos: usize,
+}
+
+impl<'script> Iterator for Instructions<'script> {
+    type Item = Result<(usize, Instr<'script>), InscriptionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.pos;
+        let op = *self.script.get(pos)?;
+        let (offset, len) = match op {
+            0x00..=0x4b => (1, op as usize),
+            _ if op == TapCode::PushData1 as u8 => match self.script.get(pos + 1..pos + 2) {
+                Some(len) => (2, len[0] as usize),
+                None => return self.truncated(pos),
+            },
+            _ if op == TapCode::PushData2 as u8 => match self.script.get(pos + 1..pos + 3) {
+                Some(len) => (3, u16::from_le_bytes([len[0], len[1]]) as usize),
+                None => return self.truncated(pos),
+            },
+            _ if op == TapCode::PushData4 as u8 => match self.script.get(pos + 1..pos + 5) {
+                Some(len) => (5, u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize),
+                None => return self.truncated(pos),
+            },
+            _ => {
+                self.pos += 1;
+                return Some(Ok((pos, Instr::Op(op))));
+            }
+        };
+        let start = pos + offset;
+        let Some(data) = start
+            .checked_add(len)
+            .and_then(|end| self.script.get(start..end))
+        else {
+            return self.truncated(pos);
+        };
+        self.pos = start + len;
+        Some(Ok((pos, Instr::Push(data))))
+    }
+}
+
+impl Instructions<'_> {
+    fn truncated<T>(&mut self, pos: usize) -> Option<Result<T, InscriptionError>> {
+        self.pos = self.script.len();
+        Some(Err(InscriptionError::Truncated { pos }))
+    }
+}
+
+/// Converts push-number opcodes into the pushed byte, as `ord` does inside envelopes.
+fn push_num(op: u8) -> Option<u8> {
+    if op == TapCode::PushNumNeg1 as u8 {
+        Some(0x81)
+    } else if (TapCode::PushNum1 as u8..=TapCode::PushNum16 as u8).contains(&op) {
+        Some(op - TapCode::PushNum1 as u8 + 1)
+    } else {
+        None
+    }
+}
+
+impl Inscription {
+    /// Extracts the first inscription from a taproot leaf script.
+    pub fn from_tap_script(script: &TapScript) -> Result<Self, InscriptionError> {
+        Self::from_script_bytes(script.as_slice())
+    }
+
+    /// Extracts the first inscription from raw script bytes.
+    pub fn from_script_bytes(script: &[u8]) -> Result<Self, InscriptionError> {
+        let pushes = envelope(script)?;
+        Self::from_pushes(&pushes)
+    }
+
+    fn from_pushes(pushes: &[(usize, Vec<u8>)]) -> Result<Self, InscriptionError> {
+        let mut content_type = None;
+        let mut iter = pushes.iter();
+        let mut body = vec![];
+        while let Some((pos, tag)) = iter.next() {
+            if tag.is_empty() {
+                iter.by_ref()
+                    .for_each(|(_, chunk)| body.extend_from_slice(chunk));
+                break;
+            }
+            let Some((value_pos, value)) = iter.next() else {
+                return Err(InscriptionError::IncompleteField { pos: *pos });
+            };
+            // Tags are little-endian integers, so the parity is defined by the first byte.
+            let tag_no = tag[0];
+            match (tag.len(), tag_no) {
+                (1, TAG_CONTENT_TYPE) if content_type.is_some() => {
+                    return Err(InscriptionError::DuplicateField { tag: tag_no, pos: *pos });
+                }
+                (1, TAG_CONTENT_TYPE) => content_type = Some((*value_pos, value)),
+                (1, TAG_CONTENT_ENCODING) => {
+                    return Err(InscriptionError::ContentEncoding(String::from_utf8_lossy(value).into_owned()));
+                }
+                (1, TAG_DELEGATE) => return Err(InscriptionError::Delegated),
+                (1, 2 | 3 | 5 | 7) => {}
+                _ if tag_no % 2 == 0 => {
+                    return Err(InscriptionError::UnknownEvenField { tag: tag_no, pos: *pos });
+                }
+                _ => {}
+            }
+        }
+
+        let mime = match content_type {
+            None => MediaType::with("application/octet-stream"),
+            Some((pos, value)) => parse_content_type(value).ok_or_else(|| InscriptionError::ContentType {
+                content_type: String::from_utf8_lossy(value).into_owned(),
+                pos,
+            })?,
+        };
+        Ok(Inscription { mime, body })
+    }
+
+    /// Converts the inscription into embedded media if it fits the size limit, or into an
+    /// attachment otherwise.
+    pub fn to_media(&self) -> InscriptionMedia {
+        match SmallBlob::try_from(self.body.clone()) {
+            Ok(data) => EmbeddedMedia { mime: self.mime.clone(), data }.into(),
+            Err(_) => Attachment::with_data(self.mime.clone(), &self.body).into(),
+        }
+    }
+}
+
+/// Finds the first envelope and returns its pushes (with their script positions) following the
+/// protocol identifier.
+fn envelope(script: &[u8]) -> Result<Vec<(usize, Vec<u8>)>, InscriptionError> {
+    let mut instructions = Instructions { script, pos: 0 };
+    let mut window: [Option<(usize, Instr)>; 2] = [None, None];
+    while let Some(instr) = instructions.next() {
+        let (pos, instr) = instr?;
+        let is_start = matches!(&window, [Some((_, Instr::Push([]))), Some((_, Instr::Op(op)))] if *op == TapCode::If as u8)
+            && instr == Instr::Push(ORD_PROTOCOL_ID);
+        if !is_start {
+            window = [window[1].take(), Some((pos, instr))];
+            continue;
+        }
+        let start = window[0].as_ref().map(|(pos, _)| *pos).unwrap_or_default();
+        let mut pushes = vec![];
+        for instr in instructions.by_ref() {
+            match instr? {
+                (_, Instr::Op(op)) if op == TapCode::EndIf as u8 => return Ok(pushes),
+                (pos, Instr::Push(data)) => pushes.push((pos, data.to_vec())),
+                (pos, Instr::Op(op)) => match push_num(op) {
+                    Some(byte) => pushes.push((pos, vec![byte])),
+                    None => return Err(InscriptionError::UnexpectedOpcode { pos, opcode: op }),
+                },
+            }
+        }
+        return Err(InscriptionError::Unterminated { start });
+    }
+    Err(InscriptionError::NoEnvelope)
+}
+
+/// Parses content type, stripping its parameters except the charset.
+fn parse_content_type(value: &[u8]) -> Option<MediaType> {
+    let s = std::str::from_utf8(value).ok()?.to_ascii_lowercase();
+    let mut parts = s.split(';').map(str::trim);
+    let mut mime = MediaType::from_str(parts.next()?).ok()?;
+    mime.charset = parts
+        .filter_map(|param| param.strip_prefix("charset="))
+        .find_map(|charset| MediaRegName::from_str(charset.trim_matches('"')).ok());
+    Some(mime)
+}
+
+#[cfg(test)]
+mod test {
+    use bc::ScriptBytes;
+
+    use super::*;
+
+    fn push_slice(script: &mut Vec<u8>, data: &[u8]) {
+        let mut bytes = ScriptBytes::default();
+        bytes.push_slice(data);
+        script.extend_from_slice(bytes.as_slice());
+    }
+
+    fn envelope_script(fields: &[&[u8]]) -> Vec<u8> {
+        let mut script = vec![];
+        push_slice(&mut script, &[0x11; 32]);
+        script.extend([0xac, TapCode::PushBytes0 as u8, TapCode::If as u8]);
+        push_slice(&mut script, ORD_PROTOCOL_ID);
+        for field in fields {
+            push_slice(&mut script, field);
+        }
+        script.push(TapCode::EndIf as u8);
+        script
+    }
+
+    #[test]
+    fn text_inscription() {
+        let script = envelope_script(&[&[1], b"text/plain;charset=utf-8", &[], b"Hello, ", b"world!"]);
+        let inscription = Inscription::from_tap_script(&TapScript::from_checked(script)).unwrap();
+        assert_eq!(inscription.mime.to_string(), "text/plain");
+        assert_eq!(inscription.mime.charset, Some(MediaRegName::from("utf-8")));
+        assert_eq!(inscription.body, b"Hello, world!");
+
+        let InscriptionMedia::Embedded(media) = inscription.to_media() else {
+            panic!("small inscription must be embedded")
+        };
+        assert_eq!(media.data.as_slice(), b"Hello, world!");
+    }
+
+    #[test]
+    fn large_inscription() {
+        let chunk = [0xAB; 520];
+        let mut fields: Vec<&[u8]> = vec![&[1], b"image/png", &[]];
+        fields.extend([&chunk[..]; 130]);
+        let inscription = Inscription::from_script_bytes(&envelope_script(&fields)).unwrap();
+        assert_eq!(inscription.body.len(), 520 * 130);
+        assert_eq!(
+            inscription.to_media(),
+            InscriptionMedia::Attachment(Attachment::with_data(MediaType::with("image/png"), &inscription.body))
+        );
+    }
+
+    #[test]
+    fn push_num_tags() {
+        let mut script = vec![TapCode::PushBytes0 as u8, TapCode::If as u8];
+        push_slice(&mut script, ORD_PROTOCOL_ID);
+        script.push(TapCode::PushNum1 as u8);
+        push_slice(&mut script, b"image/svg+xml");
+        script.push(TapCode::PushNum5 as u8);
+        push_slice(&mut script, b"metadata");
+        script.push(TapCode::PushBytes0 as u8);
+        push_slice(&mut script, b"<svg/>");
+        script.push(TapCode::EndIf as u8);
+        let inscription = Inscription::from_script_bytes(&script).unwrap();
+        assert_eq!(inscription.mime, MediaType::with("image/svg+xml"));
+        assert_eq!(inscription.body, b"<svg/>");
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(Inscription::from_script_bytes(&[0x51, 0x52]), Err(InscriptionError::NoEnvelope));
+        assert_eq!(
+            Inscription::from_script_bytes(&[0x00, 0x63, 0x03, b'o', b'r', b'd', 0x4c]),
+            Err(InscriptionError::Truncated { pos: 6 })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&[0x51, 0x00, 0x63, 0x03, b'o', b'r', b'd', 0x01, 0x01]),
+            Err(InscriptionError::Unterminated { start: 1 })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&[0x00, 0x63, 0x03, b'o', b'r', b'd', 0xac, 0x68]),
+            Err(InscriptionError::UnexpectedOpcode { pos: 6, opcode: 0xac })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&envelope_script(&[&[1], b"text/plain", &[1]])),
+            Err(InscriptionError::IncompleteField { pos: 53 })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&envelope_script(&[&[1], b"text/plain", &[1], b"text/html", &[]])),
+            Err(InscriptionError::DuplicateField { tag: 1, pos: 53 })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&envelope_script(&[&[4], b"x", &[]])),
+            Err(InscriptionError::UnknownEvenField { tag: 4, pos: 40 })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&envelope_script(&[&[1], b"text plain", &[]])),
+            Err(InscriptionError::ContentType { content_type: s!("text plain"), pos: 42 })
+        );
+        assert_eq!(
+            Inscription::from_script_bytes(&envelope_script(&[&[9], b"br", &[]])),
+            Err(InscriptionError::ContentEncoding(s!("br")))
+        );
+    }
+}
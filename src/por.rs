@@ -18,28 +18,346 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use amplify::confinement::SmallBlob;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use amplify::confinement::{self, Confined, MediumBlob, SmallBlob, SmallVec, TinyVec};
 use amplify::ByteArray;
-use bc::{Outpoint, Txid};
-use strict_encoding::{StrictDeserialize, StrictSerialize};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use bc::{Outpoint, OutpointParseError, Sats, ScriptPubkey, Tx, TxOut, Txid, Vout};
+use commit_verify::{Digest, Ripemd160, Sha256};
+use strict_encoding::{DeserializeError, StrictDeserialize, StrictSerialize};
+use strict_types::value::EnumTag;
 use strict_types::StrictVal;
 
-use crate::LIB_NAME_RGB_CONTRACT;
+use crate::{Amount, Precision, LIB_NAME_RGB_CONTRACT};
 
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
+/// Maximal length of a strict-serialized reserve proof.
+const PROOF_MAX_LEN: usize = 0x0200_0000;
+
+/// Prefix of the text form of [`Layer1Ptr::WitnessOut`].
+pub const LAYER1_PTR_WITNESS_PREFIX: &str = "~";
+/// Prefix of the text form of [`Layer1Ptr::Liquid`].
+pub const LAYER1_PTR_LIQUID_PREFIX: &str = "liquid";
+/// Prefix of the text form of [`Layer1Ptr::LnFunding`].
+pub const LAYER1_PTR_LN_PREFIX: &str = "ln";
+
+/// Pointer to a bitcoin layer 1 entity.
+///
+/// The strict encoding tags are part of the `RGBContract` library and never change:
+///
+/// | Tag    | Variant      | Text form                |
+/// |--------|--------------|--------------------------|
+/// | `0x01` | `Utxo`       | `<txid>:<vout>`          |
+/// | `0x02` | `WitnessOut` | `~:<vout>`               |
+/// | `0x03` | `Liquid`     | `liquid:<txid>:<vout>`   |
+/// | `0x04` | `LnFunding`  | `ln:<txid>:<vout>`       |
+///
+/// In human-readable serde formats the pointer is serialized as a string in the text form.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, From)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = LIB_NAME_RGB_CONTRACT, tags = custom, dumb = Self::Utxo(strict_dumb!()))]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(remote = "Self"))]
 pub enum Layer1Ptr {
+    /// Bitcoin transaction output.
     #[from]
+    #[display(inner)]
     #[strict_type(tag = 0x01)]
     Utxo(Outpoint),
+
+    /// Output of the witness transaction, i.e. the transaction which carries the contract
+    /// operation itself and whose id can't be known in advance.
+    #[display("~:{0}")]
+    #[strict_type(tag = 0x02)]
+    WitnessOut(Vout),
+
+    /// Transaction output on the Liquid sidechain.
+    #[display("liquid:{0}")]
+    #[strict_type(tag = 0x03)]
+    Liquid(Outpoint),
+
+    /// Funding output of a Lightning channel.
+    #[display("ln:{0}")]
+    #[strict_type(tag = 0x04)]
+    LnFunding(Outpoint),
+}
+
+impl StrictSerialize for Layer1Ptr {}
+impl StrictDeserialize for Layer1Ptr {}
+
+impl FromStr for Layer1Ptr {
+    type Err = PorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((LAYER1_PTR_WITNESS_PREFIX, vout)) => {
+                let vout = u32::from_str(vout).map_err(|err| PorParseError::WitnessVout(vout.to_owned(), err))?;
+                Ok(Layer1Ptr::WitnessOut(Vout::from_u32(vout)))
+            }
+            Some((LAYER1_PTR_LIQUID_PREFIX, outpoint)) => Ok(Layer1Ptr::Liquid(Outpoint::from_str(outpoint)?)),
+            Some((LAYER1_PTR_LN_PREFIX, outpoint)) => Ok(Layer1Ptr::LnFunding(Outpoint::from_str(outpoint)?)),
+            _ => Ok(Layer1Ptr::Utxo(Outpoint::from_str(s)?)),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum PorParseError {
+    /// invalid reserve UTXO; {0}
+    #[from]
+    Outpoint(OutpointParseError),
+
+    /// invalid witness output number '{0}'; {1}
+    WitnessVout(String, ParseIntError),
+
+    /// proof of reserves '{0}' lacks '/' separating the UTXO from the proof.
+    NoProof(String),
+
+    /// reserve proof is not a valid base64 string; {0}
+    #[from]
+    Base64(base64::DecodeError),
+
+    /// reserve proof is too large; {0}
+    #[from]
+    ProofSize(confinement::Error),
+
+    /// invalid reserve proof data; {0}
+    #[from]
+    ProofData(DeserializeError),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum PorError {
+    /// transaction {found} doesn't match reserve UTXO transaction {expected}.
+    TxidMismatch { expected: Txid, found: Txid },
+
+    /// transaction {txid} has no output #{vout}.
+    NoOutput { txid: Txid, vout: u32 },
+
+    /// reserve UTXO {0} is not present in the UTXO set; it is either spent or unknown.
+    Unspendable(Outpoint),
+
+    /// reserve UTXO {0} is an OP_RETURN output which can't hold reserves.
+    OpReturn(Outpoint),
+
+    /// reserve UTXO {utxo} holds {found} sats, which is less than the required {required} sats.
+    InsufficientValue {
+        utxo: Outpoint,
+        required: Sats,
+        found: Sats,
+    },
+
+    /// aggregate reserves contain no UTXOs.
+    NoReserves,
+
+    /// reserve UTXO {0} is listed more than once.
+    DuplicateUtxo(Outpoint),
+
+    /// witness script of the proof for reserve UTXO {0} doesn't match the output script.
+    ScriptMismatch(Outpoint),
+
+    /// total value of the reserves overflows.
+    Overflow,
+
+    /// aggregate reserves contain {0} UTXOs, exceeding the maximum of 65535.
+    TooManyUtxos(usize),
+
+    /// reserve proof type can't be represented in the legacy proof of reserves format.
+    NonLegacyProof,
+}
+
+/// Source of unspent transaction outputs, like a full node, an indexer or a local stand-in.
+pub trait UtxoSet {
+    /// Returns the output if it exists and is not spent.
+    fn unspent(&self, outpoint: Outpoint) -> Option<TxOut>;
+}
+
+impl UtxoSet for BTreeMap<Outpoint, TxOut> {
+    fn unspent(&self, outpoint: Outpoint) -> Option<TxOut> { self.get(&outpoint).cloned() }
+}
+
+/// Result of a successful reserves verification.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ReserveReport {
+    pub utxo: Outpoint,
+    pub value: Sats,
+    pub script_pubkey: ScriptPubkey,
+    /// Whether the proof has the legacy format, whose semantics are unknown to the verifier.
+    pub legacy_proof: bool,
+    /// Whether the output script was checked against the proof.
+    pub script: ScriptCheck,
+}
+
+/// Outcome of matching the reserve output script against the proof.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[display(lowercase)]
+pub enum ScriptCheck {
+    /// The proof witness script hashes into the P2WSH (or P2SH-wrapped P2WSH) output program.
+    Verified,
+
+    /// The script can't be checked offline: either the proof carries no script, or the output is
+    /// a taproot one, whose key commits to the script through the control block, which is not a
+    /// part of the proof.
+    Unchecked,
+}
+
+/// Magic bytes starting a serialized PSBT.
+pub const PSBT_MAGIC: [u8; 5] = *b"psbt\xff";
+
+/// BIP-322 signature proving control over the reserve UTXO.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct Bip322Proof {
+    /// Challenge message provided by the auditor.
+    pub challenge: SmallBlob,
+    /// Serialized BIP-322 signature witness over the challenge.
+    pub signature: SmallBlob,
+}
+
+/// Script-path spending witness for the reserve UTXO, without the control block.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct ScriptWitness {
+    pub script: SmallBlob,
+    pub stack: TinyVec<SmallBlob>,
+}
+
+/// Proof of control over the reserve UTXO.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT, tags = custom, dumb = Self::Legacy(strict_dumb!()))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum ReserveProof {
+    /// Opaque proof of an unknown format, as used before the proof types were introduced.
+    #[from]
+    #[strict_type(tag = 0x00)]
+    Legacy(SmallBlob),
+
+    #[from]
+    #[strict_type(tag = 0x01)]
+    Bip322(Bip322Proof),
+
+    /// Serialized PSBT spending the reserve UTXO, like a BIP-127 proof of reserves.
+    #[strict_type(tag = 0x02)]
+    Psbt(MediumBlob),
+
+    #[from]
+    #[strict_type(tag = 0x03)]
+    ScriptWitness(ScriptWitness),
 }
 
+impl StrictSerialize for ReserveProof {}
+impl StrictDeserialize for ReserveProof {}
+
+impl ReserveProof {
+    pub fn bip322(challenge: impl Into<Vec<u8>>, signature: impl Into<Vec<u8>>) -> Result<Self, confinement::Error> {
+        Ok(ReserveProof::Bip322(Bip322Proof {
+            challenge: SmallBlob::try_from(challenge.into())?,
+            signature: SmallBlob::try_from(signature.into())?,
+        }))
+    }
+
+    pub fn psbt(psbt: impl Into<Vec<u8>>) -> Result<Self, confinement::Error> {
+        MediumBlob::try_from(psbt.into()).map(ReserveProof::Psbt)
+    }
+
+    pub fn script_witness(
+        script: impl Into<Vec<u8>>,
+        stack: impl IntoIterator<Item = Vec<u8>>,
+    ) -> Result<Self, confinement::Error> {
+        let stack = stack
+            .into_iter()
+            .map(SmallBlob::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ReserveProof::ScriptWitness(ScriptWitness {
+            script: SmallBlob::try_from(script.into())?,
+            stack: TinyVec::try_from(stack)?,
+        }))
+    }
+
+    /// Converts a proof blob of a legacy [`ProofOfReserves`] into a typed proof.
+    ///
+    /// Blobs holding a PSBT are recognized by their magic bytes; all other blobs are kept as
+    /// [`ReserveProof::Legacy`], since their format can't be detected reliably.
+    pub fn from_legacy_blob(blob: SmallBlob) -> Self {
+        if blob.starts_with(&PSBT_MAGIC) {
+            return ReserveProof::Psbt(MediumBlob::from_checked(blob.release()));
+        }
+        ReserveProof::Legacy(blob)
+    }
+
+    pub fn is_legacy(&self) -> bool { matches!(self, ReserveProof::Legacy(_)) }
+
+    pub fn from_strict_val_unchecked(value: &StrictVal) -> Self {
+        let StrictVal::Union(tag, inner) = value else {
+            panic!("reserve proof must be a union");
+        };
+        let bytes = |val: &StrictVal| val.unwrap_bytes().to_vec();
+        match tag {
+            EnumTag::Ord(0x00) => ReserveProof::Legacy(SmallBlob::from_checked(bytes(inner))),
+            EnumTag::Name(name) if name.as_str() == "legacy" => {
+                ReserveProof::Legacy(SmallBlob::from_checked(bytes(inner)))
+            }
+            EnumTag::Ord(0x01) => ReserveProof::Bip322(Bip322Proof::from_strict_val_unchecked(inner)),
+            EnumTag::Name(name) if name.as_str() == "bip322" => {
+                ReserveProof::Bip322(Bip322Proof::from_strict_val_unchecked(inner))
+            }
+            EnumTag::Ord(0x02) => ReserveProof::Psbt(MediumBlob::from_checked(bytes(inner))),
+            EnumTag::Name(name) if name.as_str() == "psbt" => {
+                ReserveProof::Psbt(MediumBlob::from_checked(bytes(inner)))
+            }
+            EnumTag::Ord(0x03) => ReserveProof::ScriptWitness(ScriptWitness::from_strict_val_unchecked(inner)),
+            EnumTag::Name(name) if name.as_str() == "scriptWitness" => {
+                ReserveProof::ScriptWitness(ScriptWitness::from_strict_val_unchecked(inner))
+            }
+            _ => panic!("unknown reserve proof type {tag:?}"),
+        }
+    }
+}
+
+impl Bip322Proof {
+    pub fn from_strict_val_unchecked(value: &StrictVal) -> Self {
+        Self {
+            challenge: SmallBlob::from_checked(value.unwrap_struct("challenge").unwrap_bytes().into()),
+            signature: SmallBlob::from_checked(value.unwrap_struct("signature").unwrap_bytes().into()),
+        }
+    }
+}
+
+impl ScriptWitness {
+    pub fn from_strict_val_unchecked(value: &StrictVal) -> Self {
+        let script = SmallBlob::from_checked(value.unwrap_struct("script").unwrap_bytes().into());
+        let StrictVal::List(items) = value.unwrap_struct("stack") else {
+            panic!("script witness stack must be a list");
+        };
+        let stack = items
+            .iter()
+            .map(|item| SmallBlob::from_checked(item.unwrap_bytes().into()));
+        Self { script, stack: TinyVec::from_iter_checked(stack) }
+    }
+}
+
+/// Proof of reserves held in a single UTXO, with an opaque proof blob.
+///
+/// This is the original format used by [`crate::NftSpec`] and its encoding never changes; use
+/// [`ProofOfReservesV1`] for proofs of a known type. The blob is interpreted with
+/// [`ReserveProof::from_legacy_blob`] when converting into the typed form.
+///
+/// Formatted as `<txid>:<vout>/<proof>`, where the proof is the blob encoded with base64. In
+/// human-readable serde formats the proof of reserves is serialized as a string.
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = LIB_NAME_RGB_CONTRACT, dumb = ProofOfReserves::new(strict_dumb!(), strict_dumb!()))]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(remote = "Self"))]
 pub struct ProofOfReserves {
     pub utxo: Outpoint,
     pub proof: SmallBlob,
@@ -47,9 +365,38 @@ pub struct ProofOfReserves {
 impl StrictSerialize for ProofOfReserves {}
 impl StrictDeserialize for ProofOfReserves {}
 
+impl Display for ProofOfReserves {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}/{}", self.utxo, BASE64.encode(&self.proof)) }
+}
+
+impl FromStr for ProofOfReserves {
+    type Err = PorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (utxo, proof) = split_por(s)?;
+        Ok(ProofOfReserves { utxo, proof: SmallBlob::try_from(proof)? })
+    }
+}
+
+impl TryFrom<ProofOfReservesV1> for ProofOfReserves {
+    type Error = PorError;
+
+    fn try_from(por: ProofOfReservesV1) -> Result<Self, Self::Error> {
+        let proof = match por.proof {
+            ReserveProof::Legacy(blob) => blob,
+            ReserveProof::Psbt(psbt) => SmallBlob::try_from(psbt.release()).map_err(|_| PorError::NonLegacyProof)?,
+            ReserveProof::Bip322(_) | ReserveProof::ScriptWitness(_) => return Err(PorError::NonLegacyProof),
+        };
+        Ok(ProofOfReserves { utxo: por.utxo, proof })
+    }
+}
+
 impl ProofOfReserves {
     pub fn new(utxo: Outpoint, proof: SmallBlob) -> ProofOfReserves { ProofOfReserves { utxo, proof } }
 
+    /// Interprets the proof blob, see [`ReserveProof::from_legacy_blob`].
+    pub fn typed_proof(&self) -> ReserveProof { ReserveProof::from_legacy_blob(self.proof.clone()) }
+
     pub fn from_strict_val_unchecked(value: &StrictVal) -> Self {
         let utxo = value.unwrap_struct("utxo");
         let txid = Txid::from_slice_checked(utxo.unwrap_struct("txid").unwrap_bytes());
@@ -60,4 +407,613 @@ impl ProofOfReserves {
 
         Self { utxo, proof }
     }
+
+    /// Verifies the reserves against the transaction which created the reserve UTXO, see
+    /// [`ProofOfReservesV1::verify`].
+    pub fn verify(&self, tx: &Tx, min_value: Option<Sats>) -> Result<ReserveReport, PorError> {
+        ProofOfReservesV1::from(self.clone()).verify(tx, min_value)
+    }
+
+    /// Verifies the reserves against a UTXO set, see [`ProofOfReservesV1::verify_unspent`].
+    pub fn verify_unspent(&self, utxos: &impl UtxoSet, min_value: Option<Sats>) -> Result<ReserveReport, PorError> {
+        ProofOfReservesV1::from(self.clone()).verify_unspent(utxos, min_value)
+    }
+}
+
+/// Proof of reserves held in a single UTXO, specifying the type of the proof.
+///
+/// Formatted as `<txid>:<vout>/<proof>`, where the proof is the strict-serialized
+/// [`ReserveProof`] encoded with base64. In human-readable serde formats the proof of reserves
+/// is serialized as a string.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT, dumb = ProofOfReservesV1::new(strict_dumb!(), ReserveProof::Legacy(strict_dumb!())))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(remote = "Self"))]
+pub struct ProofOfReservesV1 {
+    pub utxo: Outpoint,
+    pub proof: ReserveProof,
+}
+impl StrictSerialize for ProofOfReservesV1 {}
+impl StrictDeserialize for ProofOfReservesV1 {}
+
+impl Display for ProofOfReservesV1 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let proof = self
+            .proof
+            .to_strict_serialized::<PROOF_MAX_LEN>()
+            .expect("reserve proof size is bounded");
+        write!(f, "{}/{}", self.utxo, BASE64.encode(proof))
+    }
+}
+
+impl FromStr for ProofOfReservesV1 {
+    type Err = PorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (utxo, proof) = split_por(s)?;
+        let proof = ReserveProof::from_strict_serialized::<PROOF_MAX_LEN>(Confined::try_from(proof)?)?;
+        Ok(ProofOfReservesV1 { utxo, proof })
+    }
+}
+
+/// Migrates the legacy proof of reserves, see [`ReserveProof::from_legacy_blob`].
+impl From<ProofOfReserves> for ProofOfReservesV1 {
+    fn from(por: ProofOfReserves) -> Self {
+        ProofOfReservesV1 {
+            utxo: por.utxo,
+            proof: ReserveProof::from_legacy_blob(por.proof),
+        }
+    }
+}
+
+/// Splits text form of a proof of reserves into the UTXO and the base64-decoded proof data.
+fn split_por(s: &str) -> Result<(Outpoint, Vec<u8>), PorParseError> {
+    let (utxo, proof) = s
+        .split_once('/')
+        .ok_or_else(|| PorParseError::NoProof(s.to_owned()))?;
+    Ok((Outpoint::from_str(utxo)?, BASE64.decode(proof)?))
+}
+
+impl ProofOfReservesV1 {
+    pub fn new(utxo: Outpoint, proof: impl Into<ReserveProof>) -> ProofOfReservesV1 {
+        ProofOfReservesV1 { utxo, proof: proof.into() }
+    }
+
+    pub fn from_strict_val_unchecked(value: &StrictVal) -> Self {
+        let utxo = value.unwrap_struct("utxo");
+        let txid = Txid::from_slice_checked(utxo.unwrap_struct("txid").unwrap_bytes());
+        let vout: u32 = utxo.unwrap_struct("vout").unwrap_uint();
+        let utxo = Outpoint::new(txid, vout);
+
+        let proof = ReserveProof::from_strict_val_unchecked(value.unwrap_struct("proof"));
+
+        Self { utxo, proof }
+    }
+
+    /// Verifies the reserves against the transaction which created the reserve UTXO.
+    ///
+    /// The check is performed offline: it doesn't tell whether the UTXO is still unspent (see
+    /// [`Self::verify_unspent`]) and doesn't validate signatures inside the proof. The witness
+    /// script of a [`ReserveProof::ScriptWitness`] proof is matched against the output script
+    /// where possible; the report tells whether this happened.
+    pub fn verify(&self, tx: &Tx, min_value: Option<Sats>) -> Result<ReserveReport, PorError> {
+        let txid = tx.txid();
+        if txid != self.utxo.txid {
+            return Err(PorError::TxidMismatch { expected: self.utxo.txid, found: txid });
+        }
+        let vout = self.utxo.vout.to_u32();
+        let output = tx
+            .outputs
+            .get(self.utxo.vout.to_usize())
+            .ok_or(PorError::NoOutput { txid, vout })?;
+        self.check_output(output, min_value)
+    }
+
+    /// Verifies the reserves against a UTXO set, checking that the reserve UTXO is not spent.
+    pub fn verify_unspent(&self, utxos: &impl UtxoSet, min_value: Option<Sats>) -> Result<ReserveReport, PorError> {
+        let output = utxos
+            .unspent(self.utxo)
+            .ok_or(PorError::Unspendable(self.utxo))?;
+        self.check_output(&output, min_value)
+    }
+
+    fn check_output(&self, output: &TxOut, min_value: Option<Sats>) -> Result<ReserveReport, PorError> {
+        if output.script_pubkey.is_op_return() {
+            return Err(PorError::OpReturn(self.utxo));
+        }
+        if let Some(required) = min_value {
+            if output.value < required {
+                return Err(PorError::InsufficientValue { utxo: self.utxo, required, found: output.value });
+            }
+        }
+        Ok(ReserveReport {
+            utxo: self.utxo,
+            value: output.value,
+            script_pubkey: output.script_pubkey.clone(),
+            legacy_proof: self.proof.is_legacy(),
+            script: self.check_script(&output.script_pubkey)?,
+        })
+    }
+
+    fn check_script(&self, script_pubkey: &ScriptPubkey) -> Result<ScriptCheck, PorError> {
+        let ReserveProof::ScriptWitness(witness) = &self.proof else {
+            return Ok(ScriptCheck::Unchecked);
+        };
+        if script_pubkey.is_p2tr() {
+            return Ok(ScriptCheck::Unchecked);
+        }
+        let hash = Sha256::digest(witness.script.as_slice());
+        let mut redeem_script = vec![0x00, 0x20];
+        redeem_script.extend(hash);
+        let nested = Ripemd160::digest(Sha256::digest(redeem_script));
+        if *script_pubkey != ScriptPubkey::p2wsh(hash) && *script_pubkey != ScriptPubkey::p2sh(nested) {
+            return Err(PorError::ScriptMismatch(self.utxo));
+        }
+        Ok(ScriptCheck::Verified)
+    }
+}
+
+/// Reserve UTXO with its declared value.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct ReserveUtxo {
+    pub utxo: Outpoint,
+    pub value: Sats,
+}
+
+/// Moment at which the reserves were attested.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT, tags = custom, dumb = Self::BlockHeight(strict_dumb!()))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum AttestationTime {
+    /// Unix timestamp, in seconds.
+    #[display("at {0}")]
+    #[strict_type(tag = 0x00)]
+    Timestamp(i64),
+
+    #[display("at height {0}")]
+    #[strict_type(tag = 0x01)]
+    BlockHeight(u32),
+}
+
+/// Reserves held across multiple UTXOs, with a single proof covering all of them.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct AggregateReserves {
+    pub utxos: SmallVec<ReserveUtxo>,
+    pub attested_at: AttestationTime,
+    pub proof: ReserveProof,
+}
+impl StrictSerialize for AggregateReserves {}
+impl StrictDeserialize for AggregateReserves {}
+
+impl AggregateReserves {
+    /// Constructs validated aggregate reserves.
+    pub fn with(
+        utxos: impl IntoIterator<Item = ReserveUtxo>,
+        attested_at: AttestationTime,
+        proof: impl Into<ReserveProof>,
+    ) -> Result<Self, PorError> {
+        let utxos = utxos.into_iter().collect::<Vec<_>>();
+        if utxos.len() > u16::MAX as usize {
+            return Err(PorError::TooManyUtxos(utxos.len()));
+        }
+        let reserves = AggregateReserves {
+            utxos: SmallVec::from_checked(utxos),
+            attested_at,
+            proof: proof.into(),
+        };
+        reserves.validate()?;
+        Ok(reserves)
+    }
+
+    /// Checks that the reserves are non-empty, have no repeated UTXOs and their total value
+    /// doesn't overflow.
+    pub fn validate(&self) -> Result<(), PorError> {
+        if self.utxos.is_empty() {
+            return Err(PorError::NoReserves);
+        }
+        let mut seen = BTreeSet::new();
+        for reserve in &self.utxos {
+            if !seen.insert(reserve.utxo) {
+                return Err(PorError::DuplicateUtxo(reserve.utxo));
+            }
+        }
+        self.total().map(|_| ())
+    }
+
+    /// Sum of the declared values of all reserve UTXOs.
+    pub fn total(&self) -> Result<Sats, PorError> {
+        self.utxos
+            .iter()
+            .try_fold(0u64, |sum, reserve| sum.checked_add(reserve.value.sats()))
+            .map(Sats::from_sats)
+            .ok_or(PorError::Overflow)
+    }
+
+    /// Ratio of the total reserves to the value of the issued asset amount.
+    ///
+    /// The asset value is computed from the `issued` amount of atomic units, the asset
+    /// `precision` and the value of a single whole asset unit in sats; for assets pegged 1:1 to
+    /// bitcoin it is [`Sats::BTC`]. Returns `None` if nothing is issued.
+    ///
+    /// The ratio is computed with `f64` arithmetic and is meant for display and monitoring. Sats
+    /// values and amounts above 2^53 lose precision in the least significant digits, so the ratio
+    /// must not be used for exact solvency checks; compare the amounts directly instead.
+    pub fn collateralization(&self, issued: Amount, precision: Precision, unit_value: Sats) -> Option<f64> {
+        if issued == Amount::ZERO || unit_value == Sats::ZERO {
+            return None;
+        }
+        let total = self.total().ok()?.sats() as f64;
+        let liabilities = issued.value() as f64 * unit_value.sats() as f64 / precision.multiplier() as f64;
+        Some(total / liabilities)
+    }
+
+    /// Checks that all reserve UTXOs are unspent and hold at least the declared values, returning
+    /// the actual total value.
+    pub fn verify_unspent(&self, utxos: &impl UtxoSet) -> Result<Sats, PorError> {
+        self.validate()?;
+        let mut total = 0u64;
+        for reserve in &self.utxos {
+            let output = utxos
+                .unspent(reserve.utxo)
+                .ok_or(PorError::Unspendable(reserve.utxo))?;
+            if output.script_pubkey.is_op_return() {
+                return Err(PorError::OpReturn(reserve.utxo));
+            }
+            if output.value < reserve.value {
+                return Err(PorError::InsufficientValue {
+                    utxo: reserve.utxo,
+                    required: reserve.value,
+                    found: output.value,
+                });
+            }
+            total = total
+                .checked_add(output.value.sats())
+                .ok_or(PorError::Overflow)?;
+        }
+        Ok(Sats::from_sats(total))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod _serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    impl Serialize for Layer1Ptr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                Layer1Ptr::serialize(self, serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Layer1Ptr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            } else {
+                Layer1Ptr::deserialize(deserializer)
+            }
+        }
+    }
+
+    impl Serialize for ProofOfReserves {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                ProofOfReserves::serialize(self, serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ProofOfReserves {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            } else {
+                ProofOfReserves::deserialize(deserializer)
+            }
+        }
+    }
+
+    impl Serialize for ProofOfReservesV1 {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                ProofOfReservesV1::serialize(self, serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ProofOfReservesV1 {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            } else {
+                ProofOfReservesV1::deserialize(deserializer)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::hex::{FromHex, ToHex};
+    use bc::{LockTime, TxVer, VarIntArray};
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+
+    fn utxo() -> Outpoint {
+        Outpoint::from_str("7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1").unwrap()
+    }
+
+    #[test]
+    fn legacy_migration() {
+        let blob = SmallBlob::from_checked(b"signed".to_vec());
+        let legacy = ProofOfReserves::new(utxo(), blob.clone());
+        assert_eq!(legacy.typed_proof(), ReserveProof::Legacy(blob.clone()));
+        let por = ProofOfReservesV1::from(legacy.clone());
+        assert_eq!(por.proof, ReserveProof::Legacy(blob));
+        assert_eq!(ProofOfReserves::try_from(por), Ok(legacy));
+        let bip322 = ProofOfReservesV1::new(utxo(), ReserveProof::bip322(b"audit".to_vec(), vec![0x30; 72]).unwrap());
+        assert_eq!(ProofOfReserves::try_from(bip322), Err(PorError::NonLegacyProof));
+
+        let mut psbt = PSBT_MAGIC.to_vec();
+        psbt.extend([0x01, 0x00]);
+        let proof = ReserveProof::from_legacy_blob(SmallBlob::from_checked(psbt.clone()));
+        assert_eq!(proof, ReserveProof::psbt(psbt.clone()).unwrap());
+        assert!(!proof.is_legacy());
+        let por = ProofOfReserves::try_from(ProofOfReservesV1::new(utxo(), proof)).unwrap();
+        assert_eq!(por.proof.as_slice(), psbt);
+    }
+
+    #[test]
+    fn legacy_encoding() {
+        // Proof of reserves with the "legacy proof" blob, as encoded before the typed proofs were
+        // introduced.
+        const LEGACY: &str =
+            "d9c8b7a69584736251403f2e1d0c9b8a6f4e1d2a0b0c7f5e4b8f1e1c0b1c8d7c010000000c006c65676163792070726f6f66";
+        let data = SmallBlob::from_checked(Vec::<u8>::from_hex(LEGACY).unwrap());
+        let por = ProofOfReserves::from_strict_serialized::<0xFFFF>(data).unwrap();
+        assert_eq!(por, ProofOfReserves::new(utxo(), SmallBlob::from_checked(b"legacy proof".to_vec())));
+        assert_eq!(por.to_strict_serialized::<0xFFFF>().unwrap().to_hex(), LEGACY);
+    }
+
+    fn tx() -> Tx {
+        Tx {
+            version: TxVer::V2,
+            inputs: none!(),
+            outputs: VarIntArray::from_checked(vec![
+                TxOut::new(ScriptPubkey::op_return(b"rgb"), Sats::ZERO),
+                TxOut::new(ScriptPubkey::p2wpkh([7u8; 20]), Sats::from_sats(150_000u64)),
+            ]),
+            lock_time: LockTime::ZERO,
+        }
+    }
+
+    fn por(tx: &Tx, vout: u32) -> ProofOfReservesV1 {
+        ProofOfReservesV1::new(
+            Outpoint::new(tx.txid(), vout),
+            ReserveProof::bip322(b"audit".to_vec(), vec![0x30; 72]).unwrap(),
+        )
+    }
+
+    #[test]
+    fn verification() {
+        let tx = tx();
+        let report = por(&tx, 1)
+            .verify(&tx, Some(Sats::from_sats(100_000u64)))
+            .unwrap();
+        assert_eq!(report.value, Sats::from_sats(150_000u64));
+        assert_eq!(report.script_pubkey, ScriptPubkey::p2wpkh([7u8; 20]));
+        assert!(!report.legacy_proof);
+        assert_eq!(report.script, ScriptCheck::Unchecked);
+
+        assert_eq!(
+            por(&tx, 1).verify(&tx, Some(Sats::from_sats(200_000u64))),
+            Err(PorError::InsufficientValue {
+                utxo: Outpoint::new(tx.txid(), 1u32),
+                required: Sats::from_sats(200_000u64),
+                found: Sats::from_sats(150_000u64)
+            })
+        );
+        assert_eq!(por(&tx, 2).verify(&tx, None), Err(PorError::NoOutput { txid: tx.txid(), vout: 2 }));
+        assert_eq!(por(&tx, 0).verify(&tx, None), Err(PorError::OpReturn(Outpoint::new(tx.txid(), 0u32))));
+        assert_eq!(
+            ProofOfReservesV1::new(utxo(), ReserveProof::strict_dumb()).verify(&tx, None),
+            Err(PorError::TxidMismatch { expected: utxo().txid, found: tx.txid() })
+        );
+
+        let legacy = ProofOfReserves::new(Outpoint::new(tx.txid(), 1u32), SmallBlob::from_checked(b"sig".to_vec()));
+        assert!(legacy.verify(&tx, None).unwrap().legacy_proof);
+    }
+
+    #[test]
+    fn witness_script() {
+        let script = vec![0x51];
+        let mut tx = tx();
+        tx.outputs = VarIntArray::from_checked(vec![
+            TxOut::new(ScriptPubkey::p2wsh(Sha256::digest(&script)), Sats::from_sats(10_000u64)),
+            // P2SH-wrapped P2WSH of the same script
+            TxOut::new(
+                ScriptPubkey::from_hex("a91472c44f957fc011d97e3406667dca5b1c930c402687").unwrap(),
+                Sats::from_sats(10_000u64),
+            ),
+            TxOut::new(ScriptPubkey::p2wsh([0u8; 32]), Sats::from_sats(10_000u64)),
+            TxOut::new(ScriptPubkey::p2tr_tweaked(strict_dumb!()), Sats::from_sats(10_000u64)),
+        ]);
+        let proof = ReserveProof::script_witness(script, [vec![]]).unwrap();
+        let check = |vout: u32| {
+            ProofOfReservesV1::new(Outpoint::new(tx.txid(), vout), proof.clone())
+                .verify(&tx, None)
+                .map(|report| report.script)
+        };
+        assert_eq!(check(0), Ok(ScriptCheck::Verified));
+        assert_eq!(check(1), Ok(ScriptCheck::Verified));
+        assert_eq!(check(2), Err(PorError::ScriptMismatch(Outpoint::new(tx.txid(), 2u32))));
+        assert_eq!(check(3), Ok(ScriptCheck::Unchecked));
+    }
+
+    #[test]
+    fn utxo_set() {
+        let tx = tx();
+        let mut utxos = BTreeMap::new();
+        utxos.insert(Outpoint::new(tx.txid(), 1u32), tx.outputs[1].clone());
+        assert!(por(&tx, 1).verify_unspent(&utxos, None).is_ok());
+        utxos.clear();
+        assert_eq!(
+            por(&tx, 1).verify_unspent(&utxos, None),
+            Err(PorError::Unspendable(Outpoint::new(tx.txid(), 1u32)))
+        );
+    }
+
+    fn reserves() -> AggregateReserves {
+        let tx = tx();
+        AggregateReserves::with(
+            [
+                ReserveUtxo {
+                    utxo: Outpoint::new(tx.txid(), 1u32),
+                    value: Sats::from_sats(150_000u64),
+                },
+                ReserveUtxo { utxo: utxo(), value: Sats::from_sats(50_000u64) },
+            ],
+            AttestationTime::BlockHeight(880_000),
+            ReserveProof::psbt(PSBT_MAGIC.to_vec()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn aggregate() {
+        let reserves = reserves();
+        assert_eq!(reserves.total(), Ok(Sats::from_sats(200_000u64)));
+        // 1.6 asset units worth 125k sats each are fully backed by 200k sats
+        let ratio = reserves
+            .collateralization(Amount::from(160_000_000u64), Precision::CentiMicro, Sats::from_sats(125_000u64))
+            .unwrap();
+        assert_eq!(ratio, 1.0);
+        assert_eq!(reserves.collateralization(Amount::ZERO, Precision::CentiMicro, Sats::BTC), None);
+
+        let tx = tx();
+        let mut utxos = BTreeMap::new();
+        utxos.insert(Outpoint::new(tx.txid(), 1u32), tx.outputs[1].clone());
+        assert_eq!(reserves.verify_unspent(&utxos), Err(PorError::Unspendable(utxo())));
+        utxos.insert(utxo(), TxOut::new(ScriptPubkey::p2wpkh([8u8; 20]), Sats::from_sats(60_000u64)));
+        assert_eq!(reserves.verify_unspent(&utxos), Ok(Sats::from_sats(210_000u64)));
+
+        assert_eq!(
+            AggregateReserves::with([], AttestationTime::Timestamp(1_700_000_000), ReserveProof::strict_dumb()),
+            Err(PorError::NoReserves)
+        );
+        let many =
+            (0..=u16::MAX as u32).map(|vout| ReserveUtxo { utxo: Outpoint::new(tx.txid(), vout), value: Sats::ZERO });
+        assert_eq!(
+            AggregateReserves::with(many, AttestationTime::Timestamp(0), ReserveProof::strict_dumb()),
+            Err(PorError::TooManyUtxos(0x10000))
+        );
+        let reserve = reserves.utxos[0];
+        assert_eq!(
+            AggregateReserves::with([reserve, reserve], AttestationTime::Timestamp(0), ReserveProof::strict_dumb()),
+            Err(PorError::DuplicateUtxo(reserve.utxo))
+        );
+        let data = reserves.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(AggregateReserves::from_strict_serialized::<0xFFFF>(data).unwrap(), reserves);
+    }
+
+    #[test]
+    fn text_format() {
+        let ptr = Layer1Ptr::Utxo(utxo());
+        assert_eq!(ptr.to_string(), "7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1");
+        assert_eq!(Layer1Ptr::from_str(&ptr.to_string()), Ok(ptr));
+
+        let legacy = ProofOfReserves::new(utxo(), SmallBlob::from_checked(b"sig".to_vec()));
+        let s = legacy.to_string();
+        assert_eq!(s, "7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1/c2ln");
+        assert_eq!(ProofOfReserves::from_str(&s), Ok(legacy.clone()));
+
+        let por = ProofOfReservesV1::from(legacy);
+        let s = por.to_string();
+        assert_eq!(s, "7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1/AAMAc2ln");
+        assert_eq!(ProofOfReservesV1::from_str(&s), Ok(por));
+
+        assert!(matches!(Layer1Ptr::from_str("deadbeef"), Err(PorParseError::Outpoint(_))));
+        for ptr in [Layer1Ptr::WitnessOut(Vout::from_u32(2)), Layer1Ptr::Liquid(utxo()), Layer1Ptr::LnFunding(utxo())] {
+            assert_eq!(Layer1Ptr::from_str(&ptr.to_string()), Ok(ptr));
+        }
+        assert_eq!(Layer1Ptr::WitnessOut(Vout::from_u32(2)).to_string(), "~:2");
+        assert_eq!(
+            Layer1Ptr::LnFunding(utxo()).to_string(),
+            "ln:7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1"
+        );
+        assert!(matches!(Layer1Ptr::from_str("~:x"), Err(PorParseError::WitnessVout(..))));
+        assert!(matches!(Layer1Ptr::from_str("liquid:1"), Err(PorParseError::Outpoint(_))));
+        assert_eq!(
+            ProofOfReserves::from_str("7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1"),
+            Err(PorParseError::NoProof(s!("7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1")))
+        );
+        assert!(matches!(ProofOfReserves::from_str(&format!("{}/!!", utxo())), Err(PorParseError::Base64(_))));
+        assert!(matches!(ProofOfReservesV1::from_str(&format!("{}/Bw==", utxo())), Err(PorParseError::ProofData(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let legacy = ProofOfReserves::new(utxo(), SmallBlob::from_checked(b"sig".to_vec()));
+        let json = serde_json::to_string(&legacy).unwrap();
+        assert_eq!(json, format!("\"{legacy}\""));
+        assert_eq!(serde_json::from_str::<ProofOfReserves>(&json).unwrap(), legacy);
+        let por = ProofOfReservesV1::from(legacy);
+        let json = serde_json::to_string(&por).unwrap();
+        assert_eq!(json, format!("\"{por}\""));
+        assert_eq!(serde_json::from_str::<ProofOfReservesV1>(&json).unwrap(), por);
+        let ptr = Layer1Ptr::Utxo(utxo());
+        assert_eq!(serde_json::from_str::<Layer1Ptr>(&format!("\"{ptr}\"")).unwrap(), ptr);
+    }
+
+    #[test]
+    fn layer1_tags() {
+        for (ptr, tag) in [
+            (Layer1Ptr::Utxo(utxo()), 0x01u8),
+            (Layer1Ptr::WitnessOut(Vout::from_u32(1)), 0x02),
+            (Layer1Ptr::Liquid(utxo()), 0x03),
+            (Layer1Ptr::LnFunding(utxo()), 0x04),
+        ] {
+            let data = ptr.to_strict_serialized::<0xFF>().unwrap();
+            assert_eq!(data[0], tag);
+            assert_eq!(Layer1Ptr::from_strict_serialized::<0xFF>(data).unwrap(), ptr);
+        }
+    }
+
+    #[test]
+    fn strict_encoding() {
+        for proof in [
+            ReserveProof::strict_dumb(),
+            ReserveProof::bip322(b"audit 2025-01".to_vec(), vec![0x30; 72]).unwrap(),
+            ReserveProof::psbt(PSBT_MAGIC.to_vec()).unwrap(),
+            ReserveProof::script_witness(vec![0x51], [vec![], vec![0x01]]).unwrap(),
+        ] {
+            let por = ProofOfReservesV1::new(utxo(), proof);
+            let data = por.to_strict_serialized::<0xFFFF>().unwrap();
+            assert_eq!(ProofOfReservesV1::from_strict_serialized::<0xFFFF>(data).unwrap(), por);
+        }
+    }
 }
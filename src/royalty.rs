@@ -0,0 +1,284 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::confinement::{self, TinyVec};
+use strict_encoding::stl::AsciiPrintable;
+use strict_encoding::{RString, StrictDeserialize, StrictSerialize};
+
+use crate::{Amount, Layer1Ptr, LIB_NAME_RGB21};
+
+/// Number of basis points making up 100%.
+pub const BASIS_POINTS_FULL: u16 = 10_000;
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum RoyaltyError {
+    /// royalty share of {0} has zero basis points.
+    ZeroShare(RoyaltyRecipient),
+
+    /// royalty shares sum up to {0}, which exceeds 100%.
+    Excessive(BasisPoints),
+
+    /// recipient {0} is listed more than once.
+    DuplicateRecipient(RoyaltyRecipient),
+
+    /// too many royalty recipients; {0}
+    #[from]
+    Confinement(confinement::Error),
+}
+
+/// Share expressed in basis points, i.e. in hundredths of a percent.
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, From)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct BasisPoints(u16);
+
+impl BasisPoints {
+    pub const ZERO: Self = BasisPoints(0);
+    pub const FULL: Self = BasisPoints(BASIS_POINTS_FULL);
+
+    pub fn value(self) -> u16 { self.0 }
+
+    /// Computes the share of an amount, rounding down. Shares above 100% which would exceed the
+    /// maximum amount are saturated.
+    pub fn share_of(self, amount: Amount) -> Amount {
+        let share = amount.value() as u128 * self.0 as u128 / BASIS_POINTS_FULL as u128;
+        Amount::from(share.min(u64::MAX as u128) as u64)
+    }
+}
+
+/// Formats basis points as percents, like `2.50%`.
+impl Display for BasisPoints {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}.{:02}%", self.0 / 100, self.0 % 100) }
+}
+
+/// Opaque identifier of a royalty beneficiary outside of bitcoin layer 1, like an invoice or an
+/// account name.
+#[derive(Wrapper, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, From)]
+#[wrapper(Deref, Display, FromStr)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Beneficiary(RString<AsciiPrintable, AsciiPrintable, 1, 0xFF>);
+
+impl_ident_type!(Beneficiary);
+
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, From)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21, tags = custom, dumb = Self::Beneficiary(strict_dumb!()))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum RoyaltyRecipient {
+    #[from]
+    #[display(inner)]
+    #[strict_type(tag = 0x01)]
+    Layer1(Layer1Ptr),
+
+    #[from]
+    #[display(inner)]
+    #[strict_type(tag = 0x02)]
+    Beneficiary(Beneficiary),
+}
+
+/// Whether royalties are expected to be enforced.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum RoyaltyPolicy {
+    /// Royalties are the creator wish; marketplaces may ignore them.
+    #[default]
+    Advisory = 0,
+
+    /// Marketplaces are expected to refuse sales which don't pay royalties.
+    Enforced = 1,
+}
+
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct RoyaltyShare {
+    pub recipient: RoyaltyRecipient,
+    pub share: BasisPoints,
+}
+
+/// Royalty declaration of an NFT collection.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct Royalty {
+    pub policy: RoyaltyPolicy,
+    pub shares: TinyVec<RoyaltyShare>,
+}
+impl StrictSerialize for Royalty {}
+impl StrictDeserialize for Royalty {}
+
+impl Royalty {
+    /// Constructs validated royalty declaration.
+    pub fn with(
+        policy: RoyaltyPolicy,
+        shares: impl IntoIterator<Item = (RoyaltyRecipient, BasisPoints)>,
+    ) -> Result<Self, RoyaltyError> {
+        let shares = shares
+            .into_iter()
+            .map(|(recipient, share)| RoyaltyShare { recipient, share });
+        let royalty = Royalty { policy, shares: TinyVec::try_from_iter(shares)? };
+        royalty.validate()?;
+        Ok(royalty)
+    }
+
+    /// Checks that all shares are non-zero, recipients are unique and the total doesn't exceed
+    /// 100%.
+    pub fn validate(&self) -> Result<(), RoyaltyError> {
+        let mut recipients = BTreeSet::new();
+        let mut total = 0u32;
+        for share in &self.shares {
+            if share.share == BasisPoints::ZERO {
+                return Err(RoyaltyError::ZeroShare(share.recipient.clone()));
+            }
+            if !recipients.insert(&share.recipient) {
+                return Err(RoyaltyError::DuplicateRecipient(share.recipient.clone()));
+            }
+            total += share.share.value() as u32;
+        }
+        if total > BASIS_POINTS_FULL as u32 {
+            return Err(RoyaltyError::Excessive(BasisPoints(total.min(u16::MAX as u32) as u16)));
+        }
+        Ok(())
+    }
+
+    /// Sum of all royalty shares.
+    pub fn total_share(&self) -> BasisPoints {
+        let total = self
+            .shares
+            .iter()
+            .map(|share| share.share.value() as u32)
+            .sum::<u32>();
+        BasisPoints(total.min(u16::MAX as u32) as u16)
+    }
+
+    /// Computes royalty amounts due to each recipient for a given sale price.
+    ///
+    /// Each amount is rounded down independently, so the result doesn't depend on the order of
+    /// the recipients; the rounding remainder stays with the seller.
+    pub fn amounts(&self, price: Amount) -> Vec<(&RoyaltyRecipient, Amount)> {
+        self.shares
+            .iter()
+            .map(|share| (&share.recipient, share.share.share_of(price)))
+            .collect()
+    }
+
+    /// Computes total royalty for a given sale price, which is the sum of [`Self::amounts`].
+    ///
+    /// Returns `None` if the sum overflows, which is possible only for royalty declarations which
+    /// don't pass [`Self::validate`].
+    pub fn total_amount(&self, price: Amount) -> Option<Amount> {
+        self.amounts(price)
+            .into_iter()
+            .try_fold(Amount::ZERO, |sum, (_, amount)| sum.checked_add(amount))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bc::Outpoint;
+
+    use super::*;
+
+    fn beneficiary(s: &str) -> RoyaltyRecipient { Beneficiary::from_str(s).unwrap().into() }
+
+    fn utxo() -> RoyaltyRecipient {
+        Layer1Ptr::Utxo(
+            Outpoint::from_str("7c8d1c0b1c1e8f4b5e7f0c0b2a1d4e6f8a9b0c1d2e3f405162738495a6b7c8d9:1").unwrap(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn amounts() {
+        let royalty = Royalty::with(RoyaltyPolicy::Enforced, [
+            (beneficiary("artist@example.com"), BasisPoints::from(250)),
+            (utxo(), BasisPoints::from(133)),
+        ])
+        .unwrap();
+        assert_eq!(royalty.total_share().to_string(), "3.83%");
+
+        let amounts = royalty.amounts(Amount::from(999u64));
+        assert_eq!(amounts[0].1, Amount::from(24u64));
+        assert_eq!(amounts[1].1, Amount::from(13u64));
+        assert_eq!(royalty.total_amount(Amount::from(999u64)), Some(Amount::from(37u64)));
+        assert_eq!(royalty.total_amount(Amount::from(u64::MAX)), Some(Amount::from(706510298023075826u64)));
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(
+            Royalty::with(RoyaltyPolicy::Advisory, [
+                (beneficiary("a"), BasisPoints::from(6000)),
+                (beneficiary("b"), BasisPoints::from(4001)),
+            ]),
+            Err(RoyaltyError::Excessive(BasisPoints::from(10001)))
+        );
+        assert_eq!(
+            Royalty::with(RoyaltyPolicy::Advisory, [
+                (utxo(), BasisPoints::from(100)),
+                (utxo(), BasisPoints::from(100)),
+            ]),
+            Err(RoyaltyError::DuplicateRecipient(utxo()))
+        );
+        assert_eq!(
+            Royalty::with(RoyaltyPolicy::Advisory, [(beneficiary("a"), BasisPoints::ZERO)]),
+            Err(RoyaltyError::ZeroShare(beneficiary("a")))
+        );
+        assert!(Royalty::with(RoyaltyPolicy::Advisory, [(beneficiary("a"), BasisPoints::FULL)]).is_ok());
+    }
+
+    #[test]
+    fn unvalidated() {
+        let shares = [beneficiary("a"), beneficiary("b")]
+            .map(|recipient| RoyaltyShare { recipient, share: BasisPoints::from(60000) });
+        let royalty = Royalty {
+            policy: RoyaltyPolicy::Enforced,
+            shares: TinyVec::from_checked(shares.to_vec()),
+        };
+        let data = royalty.to_strict_serialized::<0xFFFF>().unwrap();
+        let royalty = Royalty::from_strict_serialized::<0xFFFF>(data).unwrap();
+        assert!(royalty.validate().is_err());
+
+        assert_eq!(BasisPoints::from(60000).share_of(Amount::from(u64::MAX)), Amount::from(u64::MAX));
+        assert_eq!(royalty.total_amount(Amount::from(100u64)), Some(Amount::from(1200u64)));
+        assert_eq!(royalty.total_amount(Amount::from(u64::MAX)), None);
+    }
+
+    #[test]
+    fn strict_encoding() {
+        let royalty = Royalty::with(RoyaltyPolicy::Enforced, [(utxo(), BasisPoints::from(500))]).unwrap();
+        let data = royalty.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(Royalty::from_strict_serialized::<0xFFFF>(data).unwrap(), royalty);
+    }
+}
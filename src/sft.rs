@@ -0,0 +1,349 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::confinement::{self, SmallOrdMap};
+use amplify::Wrapper;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use crate::{Amount, AssetName, Details, Nft, OwnedNft, Precision, TokenNo, LIB_NAME_RGB_SFT};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum SftError {
+    /// token class {0} is not defined.
+    UnknownClass(ClassId),
+
+    /// token class {0} is defined more than once.
+    DuplicateClass(ClassId),
+
+    /// token class {class} has supply of {supply}, but {allocated} is allocated.
+    ExceedsSupply {
+        class: ClassId,
+        supply: Amount,
+        allocated: Amount,
+    },
+
+    /// amount of token class {0} overflows.
+    Overflow(ClassId),
+
+    /// balance of token class {class} is {available}, which is less than {requested}.
+    Insufficient {
+        class: ClassId,
+        available: Amount,
+        requested: Amount,
+    },
+
+    /// too many token classes; {0}
+    #[from]
+    Confinement(confinement::Error),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SftParseError {
+    /// allocation must have format <amount>@<class_id>.
+    WrongFormat,
+
+    /// invalid amount '{0}'.
+    InvalidAmount(String),
+
+    /// invalid class id '{0}'.
+    InvalidClass(String),
+}
+
+/// Identifier of a semi-fungible token class.
+#[derive(Wrapper, WrapperMut, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Display, FromStr)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SFT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct ClassId(u32);
+
+impl From<TokenNo> for ClassId {
+    fn from(no: TokenNo) -> Self { ClassId(no.into_inner()) }
+}
+
+impl From<ClassId> for TokenNo {
+    fn from(id: ClassId) -> Self { TokenNo::from(id.0) }
+}
+
+/// Specification of a semi-fungible token class: a kind of item with a fungible supply.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SFT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct SftClass {
+    pub name: AssetName,
+    pub details: Option<Details>,
+    pub precision: Precision,
+    /// Total supply of the class, in atomic units.
+    pub supply: Amount,
+}
+
+impl SftClass {
+    /// Formats an amount of the class tokens as a decimal number according to the class
+    /// precision.
+    pub fn format_amount(&self, amount: Amount) -> String {
+        let (int, fract) = amount.split(self.precision);
+        let decimals = self.precision.decimals() as usize;
+        if decimals == 0 {
+            return int.to_string();
+        }
+        format!("{int}.{fract:0decimals$}")
+    }
+}
+
+/// Set of semi-fungible token classes defined by a contract.
+#[derive(Wrapper, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SFT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct SftClasses(SmallOrdMap<ClassId, SftClass>);
+
+impl StrictSerialize for SftClasses {}
+impl StrictDeserialize for SftClasses {}
+
+impl SftClasses {
+    pub fn with(classes: impl IntoIterator<Item = (ClassId, SftClass)>) -> Result<Self, SftError> {
+        let mut map = BTreeMap::new();
+        for (id, class) in classes {
+            if map.insert(id, class).is_some() {
+                return Err(SftError::DuplicateClass(id));
+            }
+        }
+        Ok(Self(SmallOrdMap::try_from(map)?))
+    }
+
+    pub fn class(&self, id: ClassId) -> Result<&SftClass, SftError> {
+        self.0.get(&id).ok_or(SftError::UnknownClass(id))
+    }
+
+    /// Checks that the balances refer to the known classes and don't exceed their supply.
+    pub fn check_balances(&self, balances: &SftBalances) -> Result<(), SftError> {
+        for (id, allocated) in balances.iter() {
+            let class = self.class(*id)?;
+            if *allocated > class.supply {
+                return Err(SftError::ExceedsSupply { class: *id, supply: class.supply, allocated: *allocated });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Allocation of some amount of a semi-fungible token class.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SFT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct SftAllocation {
+    pub class_id: ClassId,
+    pub amount: Amount,
+}
+
+impl StrictSerialize for SftAllocation {}
+impl StrictDeserialize for SftAllocation {}
+
+impl SftAllocation {
+    pub fn new(class_id: impl Into<ClassId>, amount: impl Into<Amount>) -> Self {
+        SftAllocation { class_id: class_id.into(), amount: amount.into() }
+    }
+}
+
+/// Interprets token fractions as the amount of the class with the same number.
+impl From<Nft> for SftAllocation {
+    fn from(nft: Nft) -> Self { SftAllocation::new(nft.token_no, nft.fractions.value()) }
+}
+
+/// Interprets token fractions as the amount of the class with the same number.
+impl From<OwnedNft> for SftAllocation {
+    fn from(nft: OwnedNft) -> Self { SftAllocation::new(nft.token_no, nft.fractions.value()) }
+}
+
+impl From<SftAllocation> for OwnedNft {
+    fn from(alloc: SftAllocation) -> Self { OwnedNft::new(alloc.class_id, alloc.amount.value()) }
+}
+
+/// Formats allocation as `<amount>@<class_id>`, using atomic units for the amount.
+impl Display for SftAllocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}@{}", self.amount, self.class_id) }
+}
+
+impl FromStr for SftAllocation {
+    type Err = SftParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (amount, class) = s.split_once('@').ok_or(SftParseError::WrongFormat)?;
+        Ok(SftAllocation {
+            class_id: class
+                .parse()
+                .map_err(|_| SftParseError::InvalidClass(class.to_owned()))?,
+            amount: amount
+                .parse()
+                .map_err(|_| SftParseError::InvalidAmount(amount.to_owned()))?,
+        })
+    }
+}
+
+/// Amounts of semi-fungible tokens per class, like a wallet balance or the inputs of a transfer.
+#[derive(Wrapper, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+pub struct SftBalances(BTreeMap<ClassId, Amount>);
+
+impl SftBalances {
+    pub fn new() -> Self { Self::default() }
+
+    /// Sums allocations per class.
+    pub fn with(allocations: impl IntoIterator<Item = SftAllocation>) -> Result<Self, SftError> {
+        let mut balances = Self::new();
+        for alloc in allocations {
+            balances.add(alloc)?;
+        }
+        Ok(balances)
+    }
+
+    pub fn balance(&self, class_id: ClassId) -> Amount { self.0.get(&class_id).copied().unwrap_or_default() }
+
+    pub fn add(&mut self, alloc: SftAllocation) -> Result<(), SftError> {
+        let balance = self.0.entry(alloc.class_id).or_default();
+        balance
+            .checked_add_assign(alloc.amount)
+            .ok_or(SftError::Overflow(alloc.class_id))
+    }
+
+    pub fn sub(&mut self, alloc: SftAllocation) -> Result<(), SftError> {
+        let available = self.balance(alloc.class_id);
+        let rest = available
+            .checked_sub(alloc.amount)
+            .ok_or(SftError::Insufficient { class: alloc.class_id, available, requested: alloc.amount })?;
+        if rest == Amount::ZERO {
+            self.0.remove(&alloc.class_id);
+        } else {
+            self.0.insert(alloc.class_id, rest);
+        }
+        Ok(())
+    }
+
+    /// Checks that a transfer preserves the amounts of each class, i.e. the inputs and outputs
+    /// have equal balances.
+    pub fn is_balanced(&self, other: &Self) -> bool {
+        let nonzero = |balances: &Self| {
+            balances
+                .0
+                .iter()
+                .filter(|(_, amount)| **amount != Amount::ZERO)
+                .map(|(id, amount)| (*id, *amount))
+                .collect::<BTreeMap<_, _>>()
+        };
+        nonzero(self) == nonzero(other)
+    }
+
+    pub fn allocations(&self) -> impl Iterator<Item = SftAllocation> + '_ {
+        self.0
+            .iter()
+            .map(|(class_id, amount)| SftAllocation { class_id: *class_id, amount: *amount })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn alloc(class: u32, amount: u64) -> SftAllocation { SftAllocation::new(class, amount) }
+
+    fn classes() -> SftClasses {
+        SftClasses::with([
+            (ClassId::from(7u32), SftClass {
+                name: AssetName::from_str("Healing Potion").unwrap(),
+                details: None,
+                precision: Precision::Indivisible,
+                supply: Amount::from(1000u64),
+            }),
+            (ClassId::from(8u32), SftClass {
+                name: AssetName::from_str("Gold").unwrap(),
+                details: None,
+                precision: Precision::CentiMicro,
+                supply: Amount::from(1_000_000_000u64),
+            }),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn accounting() {
+        let classes = classes();
+        let mut balances = SftBalances::with([alloc(7, 600), alloc(7, 400)]).unwrap();
+        assert_eq!(balances.balance(ClassId::from(7u32)), Amount::from(1000u64));
+        assert_eq!(classes.check_balances(&balances), Ok(()));
+
+        balances.add(alloc(7, 1)).unwrap();
+        assert_eq!(
+            classes.check_balances(&balances),
+            Err(SftError::ExceedsSupply {
+                class: ClassId::from(7u32),
+                supply: Amount::from(1000u64),
+                allocated: Amount::from(1001u64)
+            })
+        );
+        let unknown = SftBalances::with([alloc(9, 1)]).unwrap();
+        assert_eq!(classes.check_balances(&unknown), Err(SftError::UnknownClass(ClassId::from(9u32))));
+        balances.add(alloc(9, 1)).unwrap();
+
+        assert_eq!(
+            balances.sub(alloc(9, 2)),
+            Err(SftError::Insufficient {
+                class: ClassId::from(9u32),
+                available: Amount::from(1u64),
+                requested: Amount::from(2u64)
+            })
+        );
+        balances.sub(alloc(9, 1)).unwrap();
+        let outputs = SftBalances::with([alloc(7, 1000), alloc(7, 1)]).unwrap();
+        assert!(balances.is_balanced(&outputs));
+        assert_eq!(SftBalances::with([alloc(1, u64::MAX), alloc(1, 1)]), Err(SftError::Overflow(ClassId::from(1u32))));
+    }
+
+    #[test]
+    fn conversions() {
+        let parsed = SftAllocation::from_str("250@7").unwrap();
+        assert_eq!(parsed, alloc(7, 250));
+        assert_eq!(parsed.to_string(), "250@7");
+        assert_eq!(OwnedNft::from(parsed), OwnedNft::new(7, 250u64));
+        assert_eq!(SftAllocation::from(Nft::new(7, 250u64)), parsed);
+        assert_eq!(SftAllocation::from_str("250"), Err(SftParseError::WrongFormat));
+
+        let classes = classes();
+        let gold = classes.class(ClassId::from(8u32)).unwrap();
+        assert_eq!(gold.format_amount(Amount::from(150_000_000u64)), "1.50000000");
+        let potion = classes.class(ClassId::from(7u32)).unwrap();
+        assert_eq!(potion.format_amount(Amount::from(3u64)), "3");
+    }
+
+    #[test]
+    fn strict_encoding() {
+        let classes = classes();
+        let data = classes.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(SftClasses::from_strict_serialized::<0xFFFF>(data).unwrap(), classes);
+    }
+}
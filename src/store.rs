@@ -0,0 +1,353 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use amplify::confinement::SmallBlob;
+use amplify::Bytes32;
+
+use crate::{Attachment, AttachmentError, DigestAlgo, EmbeddedMedia, MediaType, NftSpec};
+
+const TMP_EXT: &str = "tmp";
+
+/// Age after which a temporary file is considered to be a leftover of an interrupted write.
+const TMP_MAX_AGE: Duration = Duration::from_secs(3600);
+
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum StoreError {
+    /// I/O error in the media store; {0}
+    #[from]
+    Io(io::Error),
+
+    /// stored media is corrupted; {0}
+    #[from]
+    Corrupted(AttachmentError),
+
+    /// media {0} is absent in the store.
+    NotFound(Bytes32),
+
+    /// media {digest} has {len} bytes, which exceeds the embedded media size limit.
+    TooLarge { digest: Bytes32, len: usize },
+}
+
+/// Content-addressed filesystem storage for media data, keyed by the [`Attachment`] digest.
+///
+/// Each media is stored in a separate file named after the hex representation of its SHA-256
+/// digest. Writes are atomic: the data are written to a temporary file, which is synced and then
+/// renamed into its final location. All reads are verified against the digest.
+///
+/// Media type is not a part of the key and is not stored; it is taken from the attachment when the
+/// media is read.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MediaStore {
+    root: PathBuf,
+}
+
+impl MediaStore {
+    /// Opens media store in the given directory, creating the directory if necessary.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        Ok(MediaStore { root })
+    }
+
+    pub fn root(&self) -> &Path { &self.root }
+
+    /// Path to the file which holds (or will hold) the media with a given digest.
+    pub fn path(&self, digest: Bytes32) -> PathBuf { self.root.join(digest.to_string()) }
+
+    /// Stores media data, returning the attachment which references it.
+    pub fn put(&self, mime: MediaType, data: impl AsRef<[u8]>) -> Result<Attachment, StoreError> {
+        let data = data.as_ref();
+        let attachment = Attachment::with_data(mime, data);
+        self.write(attachment.digest, data)?;
+        Ok(attachment)
+    }
+
+    /// Stores media data after checking that they match the attachment.
+    pub fn put_verified(&self, attachment: &Attachment, data: impl AsRef<[u8]>) -> Result<(), StoreError> {
+        let data = data.as_ref();
+        attachment.verify(data)?;
+        self.write(attachment.digest, data)
+    }
+
+    /// Reads media data, verifying them against the attachment digest.
+    pub fn get(&self, attachment: &Attachment) -> Result<Vec<u8>, StoreError> {
+        let data = match fs::read(self.path(attachment.digest)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(StoreError::NotFound(attachment.digest));
+            }
+            res => res?,
+        };
+        attachment.verify(&data)?;
+        Ok(data)
+    }
+
+    /// Checks presence of the media in the store without reading and verifying it.
+    pub fn has(&self, attachment: &Attachment) -> bool { self.path(attachment.digest).is_file() }
+
+    /// Removes media from the store. Returns whether the media was present.
+    pub fn remove(&self, attachment: &Attachment) -> Result<bool, StoreError> {
+        match fs::remove_file(self.path(attachment.digest)) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Lists digests of all media present in the store.
+    pub fn list(&self) -> Result<BTreeSet<Bytes32>, StoreError> {
+        let mut digests = BTreeSet::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if let Some(digest) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| Bytes32::from_str(name).ok())
+            {
+                digests.insert(digest);
+            }
+        }
+        Ok(digests)
+    }
+
+    /// Removes all media which are not referenced by the provided attachments, together with the
+    /// leftovers of interrupted writes. Returns digests of the removed media.
+    ///
+    /// Temporary files younger than an hour are kept, since they may belong to a write which is
+    /// still in progress.
+    pub fn gc<'a>(&self, keep: impl IntoIterator<Item = &'a Attachment>) -> Result<Vec<Bytes32>, StoreError> {
+        let keep = keep.into_iter().map(|a| a.digest).collect::<BTreeSet<_>>();
+        let mut removed = vec![];
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == TMP_EXT) {
+                let age = entry.metadata()?.modified()?.elapsed().unwrap_or_default();
+                if age > TMP_MAX_AGE {
+                    fs::remove_file(path)?;
+                }
+                continue;
+            }
+            let Some(digest) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| Bytes32::from_str(name).ok())
+            else {
+                continue;
+            };
+            if !keep.contains(&digest) {
+                fs::remove_file(path)?;
+                removed.push(digest);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Stores embedded media, returning an attachment which can be used to read it back.
+    pub fn put_embedded(&self, media: &EmbeddedMedia) -> Result<Attachment, StoreError> {
+        self.put(media.mime.clone(), &media.data)
+    }
+
+    /// Reads media as embedded media.
+    pub fn get_embedded(&self, attachment: &Attachment) -> Result<EmbeddedMedia, StoreError> {
+        let data = self.get(attachment)?;
+        let len = data.len();
+        let data = SmallBlob::try_from(data).map_err(|_| StoreError::TooLarge { digest: attachment.digest, len })?;
+        Ok(EmbeddedMedia { mime: attachment.mime.clone(), data })
+    }
+
+    /// Materializes all media embedded into the NFT specification. Returns attachments for all
+    /// media referenced by the specification, including the external one, which must be put into
+    /// the store separately (see [`Self::missing`]).
+    pub fn put_spec(&self, spec: &NftSpec) -> Result<Vec<Attachment>, StoreError> {
+        self.put_embedded(&spec.embedded)?;
+        Ok(spec.attachments())
+    }
+
+    /// Returns attachments referenced by the NFT specification which are absent in the store.
+    pub fn missing(&self, spec: &NftSpec) -> Vec<Attachment> {
+        spec.attachments()
+            .into_iter()
+            .filter(|a| !self.has(a))
+            .collect()
+    }
+
+    /// Reads all media referenced by the NFT specification, verifying their integrity.
+    pub fn get_spec(&self, spec: &NftSpec) -> Result<Vec<(Attachment, Vec<u8>)>, StoreError> {
+        spec.attachments()
+            .into_iter()
+            .map(|a| self.get(&a).map(|data| (a, data)))
+            .collect()
+    }
+
+    fn write(&self, digest: Bytes32, data: &[u8]) -> Result<(), StoreError> {
+        let path = self.path(digest);
+        // Existing file is kept only if it is intact; otherwise it gets replaced.
+        if fs::read(&path).is_ok_and(|existing| DigestAlgo::Sha256.digest(existing) == digest) {
+            return Ok(());
+        }
+        let tmp = self.root.join(format!(
+            ".{digest}.{}-{}.{TMP_EXT}",
+            process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let res = File::create(&tmp)
+            .and_then(|mut file| {
+                file.write_all(data)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp, &path));
+        if res.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        Ok(res?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    struct TmpDir(PathBuf);
+
+    impl TmpDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("rgb-media-store-{name}-{}", process::id()));
+            let _ = fs::remove_dir_all(&path);
+            TmpDir(path)
+        }
+    }
+
+    impl Drop for TmpDir {
+        fn drop(&mut self) { let _ = fs::remove_dir_all(&self.0); }
+    }
+
+    fn spec() -> NftSpec {
+        NftSpec {
+            name: None,
+            embedded: EmbeddedMedia {
+                mime: MediaType::with("image/png"),
+                data: SmallBlob::try_from(b"preview".to_vec()).unwrap(),
+            },
+            external: Some(Attachment::with_data(MediaType::with("image/png"), b"full image")),
+            reserves: None,
+        }
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let dir = TmpDir::new("basic");
+        let store = MediaStore::open(&dir.0).unwrap();
+        let attachment = store.put(MediaType::with("text/plain"), b"data").unwrap();
+        assert!(store.has(&attachment));
+        assert_eq!(store.get(&attachment).unwrap(), b"data");
+        assert_eq!(store.list().unwrap(), bset![attachment.digest]);
+
+        let other = Attachment::with_data(MediaType::with("text/plain"), b"other");
+        assert!(matches!(store.put_verified(&other, b"data"), Err(StoreError::Corrupted(_))));
+        assert!(matches!(store.get(&other), Err(StoreError::NotFound(d)) if d == other.digest));
+
+        assert!(store.remove(&attachment).unwrap());
+        assert!(!store.remove(&attachment).unwrap());
+        assert!(!store.has(&attachment));
+    }
+
+    #[test]
+    fn verify_on_read() {
+        let dir = TmpDir::new("verify");
+        let store = MediaStore::open(&dir.0).unwrap();
+        let attachment = store.put(MediaType::with("text/plain"), b"data").unwrap();
+        fs::write(store.path(attachment.digest), b"tampered").unwrap();
+        assert!(matches!(store.get(&attachment), Err(StoreError::Corrupted(_))));
+    }
+
+    #[test]
+    fn gc() {
+        let dir = TmpDir::new("gc");
+        let store = MediaStore::open(&dir.0).unwrap();
+        let a = store.put(MediaType::with("text/plain"), b"a").unwrap();
+        let b = store.put(MediaType::with("text/plain"), b"b").unwrap();
+        let stale = dir.0.join(format!(".{}.1-1.tmp", a.digest));
+        fs::write(&stale, b"partial").unwrap();
+        File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(SystemTime::now() - TMP_MAX_AGE * 2)
+            .unwrap();
+        let fresh = dir.0.join(format!(".{}.1-2.tmp", b.digest));
+        fs::write(&fresh, b"in progress").unwrap();
+        fs::write(dir.0.join("README"), b"unrelated").unwrap();
+
+        assert_eq!(store.gc([&a]).unwrap(), vec![b.digest]);
+        assert!(store.has(&a));
+        assert!(!store.has(&b));
+        assert!(!stale.exists());
+        assert!(fresh.exists());
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn repair() {
+        let dir = TmpDir::new("repair");
+        let store = MediaStore::open(&dir.0).unwrap();
+        let attachment = store.put(MediaType::with("text/plain"), b"data").unwrap();
+        fs::write(store.path(attachment.digest), b"da").unwrap();
+        assert!(matches!(store.get(&attachment), Err(StoreError::Corrupted(_))));
+
+        store.put_verified(&attachment, b"data").unwrap();
+        assert_eq!(store.get(&attachment).unwrap(), b"data");
+    }
+
+    #[test]
+    fn spec_roundtrip() {
+        let dir = TmpDir::new("spec");
+        let spec = spec();
+        {
+            let store = MediaStore::open(&dir.0).unwrap();
+            let attachments = store.put_spec(&spec).unwrap();
+            assert_eq!(attachments.len(), 2);
+            assert_eq!(store.missing(&spec), vec![spec.external.clone().unwrap()]);
+            store
+                .put_verified(spec.external.as_ref().unwrap(), b"full image")
+                .unwrap();
+            assert!(store.missing(&spec).is_empty());
+        }
+
+        let store = MediaStore::open(&dir.0).unwrap();
+        let media = store.get_spec(&spec).unwrap();
+        assert_eq!(media[0].1, b"preview");
+        assert_eq!(media[1].1, b"full image");
+        assert_eq!(store.get_embedded(&spec.embedded.attachment()).unwrap(), spec.embedded);
+    }
+}
@@ -0,0 +1,242 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::str::FromStr;
+
+use amplify::confinement::{Confined, U8 as U8MAX};
+use amplify::Bytes32;
+use commit_verify::{DigestExt, Sha256};
+use strict_encoding::{StrictDeserialize, StrictDumb, StrictSerialize};
+
+use crate::{
+    Attachment, AttachmentError, AttachmentV1, ContractText, DigestAlgo, MediaType, ParseMediaTypeError,
+    LIB_NAME_RGB_CONTRACT,
+};
+
+/// Tag used for computing contract terms commitments.
+pub const CONTRACT_TERMS_TAG: &str = "urn:lnp-bp:rgb:contract-terms#2026-10-18";
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum TermsError {
+    /// contract terms do not match the commitment {expected} (actual commitment is {actual}).
+    CommitmentMismatch { expected: Bytes32, actual: Bytes32 },
+
+    /// contract terms do not reference any document.
+    NoDocument,
+
+    /// document referenced by the contract terms is not provided.
+    DocumentMissing,
+
+    /// invalid media type of the terms document; {0}
+    #[from]
+    MediaType(ParseMediaTypeError),
+
+    /// terms document doesn't match the contract; {0}
+    #[from]
+    Document(AttachmentError),
+}
+
+/// Reference to an external document (like a signed PDF) containing the legal terms of a contract.
+///
+/// This is the same as [`AttachmentV1`], but defined in the `RGBContract` library, so it can be
+/// used by any contract. The media type is kept as a string, which is checked to be a valid
+/// [`MediaType`] on construction.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct TermsDocument {
+    pub mime: Confined<String, 1, U8MAX>,
+    pub algo: DigestAlgo,
+    pub digest: Bytes32,
+}
+
+impl StrictDumb for TermsDocument {
+    fn strict_dumb() -> Self {
+        TermsDocument {
+            mime: Confined::from_checked(s!("application/pdf")),
+            algo: strict_dumb!(),
+            digest: strict_dumb!(),
+        }
+    }
+}
+impl StrictSerialize for TermsDocument {}
+impl StrictDeserialize for TermsDocument {}
+
+impl From<AttachmentV1> for TermsDocument {
+    fn from(attachment: AttachmentV1) -> Self {
+        TermsDocument {
+            mime: Confined::from_checked(attachment.mime.to_string()),
+            algo: attachment.algo,
+            digest: attachment.digest,
+        }
+    }
+}
+
+impl From<Attachment> for TermsDocument {
+    fn from(attachment: Attachment) -> Self { AttachmentV1::from(attachment).into() }
+}
+
+impl TermsDocument {
+    /// Constructs document reference by hashing the document data with the given hash function.
+    pub fn with_data(mime: MediaType, algo: DigestAlgo, data: impl AsRef<[u8]>) -> Self {
+        AttachmentV1::with_data(mime, algo, data).into()
+    }
+
+    pub fn media_type(&self) -> Result<MediaType, ParseMediaTypeError> { MediaType::from_str(&self.mime) }
+
+    /// Converts the reference into an RGB21 attachment, checking the media type.
+    pub fn to_attachment(&self) -> Result<AttachmentV1, ParseMediaTypeError> {
+        Ok(AttachmentV1 {
+            mime: self.media_type()?,
+            algo: self.algo,
+            digest: self.digest,
+        })
+    }
+
+    /// Checks that the provided data match the document digest.
+    pub fn verify(&self, data: impl AsRef<[u8]>) -> Result<(), TermsError> {
+        self.to_attachment()?.verify(data)?;
+        Ok(())
+    }
+}
+
+/// Ricardian contract terms: the human-readable legal text of a contract, optionally bound to an
+/// external document by its digest.
+///
+/// Wallets display [`ContractTerms::text`] to the user and, when the terms reference a document,
+/// check that the document they downloaded is the one the issuer committed to.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct ContractTerms {
+    pub text: ContractText,
+    pub document: Option<TermsDocument>,
+}
+impl StrictSerialize for ContractTerms {}
+impl StrictDeserialize for ContractTerms {}
+
+impl ContractTerms {
+    pub fn new(text: ContractText) -> Self { ContractTerms { text, document: None } }
+
+    pub fn with_document(text: ContractText, document: impl Into<TermsDocument>) -> Self {
+        ContractTerms { text, document: Some(document.into()) }
+    }
+
+    /// Computes tagged SHA-256 commitment to the terms text and the document reference, which can
+    /// be put into a contract while the terms themselves are distributed off-chain.
+    pub fn commitment(&self) -> Bytes32 {
+        let mut engine = Sha256::from_tag(CONTRACT_TERMS_TAG).with_raw(self.text.commitment().as_slice());
+        match &self.document {
+            None => engine.input_raw(&[0]),
+            Some(doc) => {
+                engine.input_raw(&[1, doc.algo as u8]);
+                engine.input_raw(doc.digest.as_slice());
+                engine.input_raw(&[doc.mime.len() as u8]);
+                engine.input_raw(doc.mime.as_bytes());
+            }
+        }
+        Bytes32::from_byte_array(engine.finish())
+    }
+
+    /// Checks that the terms match a commitment.
+    pub fn verify(&self, commitment: Bytes32) -> Result<(), TermsError> {
+        let actual = self.commitment();
+        if actual != commitment {
+            return Err(TermsError::CommitmentMismatch { expected: commitment, actual });
+        }
+        Ok(())
+    }
+
+    /// Checks that the provided data match the document referenced by the terms.
+    pub fn verify_document(&self, data: impl AsRef<[u8]>) -> Result<(), TermsError> {
+        self.document
+            .as_ref()
+            .ok_or(TermsError::NoDocument)?
+            .verify(data)
+    }
+
+    /// Checks both the terms against the issuer commitment and, if the terms reference a document,
+    /// the document data against its digest.
+    pub fn verify_all(&self, commitment: Bytes32, document: Option<&[u8]>) -> Result<(), TermsError> {
+        self.verify(commitment)?;
+        match (&self.document, document) {
+            (None, _) => Ok(()),
+            (Some(_), None) => Err(TermsError::DocumentMissing),
+            (Some(doc), Some(data)) => doc.verify(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PDF: &[u8] = b"%PDF-1.7 terms of issue";
+
+    fn terms() -> ContractTerms {
+        let text = ContractText::markdown("# Terms\n\nThe issuer redeems each token for 1 EUR.").unwrap();
+        let doc = TermsDocument::with_data(MediaType::with("application/pdf"), DigestAlgo::Blake3, PDF);
+        ContractTerms::with_document(text, doc)
+    }
+
+    #[test]
+    fn document() {
+        let terms = terms();
+        assert_eq!(terms.document.as_ref().unwrap().mime.as_str(), "application/pdf");
+        assert_eq!(terms.verify_document(PDF), Ok(()));
+        assert!(matches!(
+            terms.verify_document(b"%PDF-1.7 forged"),
+            Err(TermsError::Document(AttachmentError::DigestMismatch { algo: DigestAlgo::Blake3, .. }))
+        ));
+        assert_eq!(ContractTerms::new(terms.text.clone()).verify_document(PDF), Err(TermsError::NoDocument));
+
+        let attachment = Attachment::with_data(MediaType::with("application/pdf"), PDF);
+        let doc = TermsDocument::from(attachment);
+        assert_eq!(doc.algo, DigestAlgo::Sha256);
+        assert_eq!(doc.verify(PDF), Ok(()));
+        assert_eq!(
+            doc.to_attachment().unwrap(),
+            AttachmentV1::with_data(MediaType::with("application/pdf"), DigestAlgo::Sha256, PDF)
+        );
+    }
+
+    #[test]
+    fn commitment() {
+        let terms = terms();
+        let commitment = terms.commitment();
+        assert_eq!(terms.verify(commitment), Ok(()));
+        assert_eq!(terms.verify_all(commitment, Some(PDF)), Ok(()));
+        assert_eq!(terms.verify_all(commitment, None), Err(TermsError::DocumentMissing));
+
+        let bare = ContractTerms::new(terms.text.clone());
+        assert_ne!(bare.commitment(), commitment);
+        assert_eq!(bare.verify_all(bare.commitment(), None), Ok(()));
+
+        let mut other = terms.clone();
+        other.document.as_mut().unwrap().algo = DigestAlgo::Sha256;
+        assert!(matches!(other.verify(commitment), Err(TermsError::CommitmentMismatch { .. })));
+
+        let data = terms.to_strict_serialized::<0x10200>().unwrap();
+        assert_eq!(ContractTerms::from_strict_serialized::<0x10200>(data).unwrap(), terms);
+    }
+}
@@ -0,0 +1,208 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::confinement::{Confined, U16 as U16MAX};
+use amplify::Bytes32;
+use commit_verify::{DigestExt, Sha256};
+use strict_encoding::{
+    DecodeError, ReadStruct, StrictDecode, StrictDeserialize, StrictDumb, StrictSerialize, TypedRead,
+};
+
+use crate::LIB_NAME_RGB_CONTRACT;
+
+/// Tag used for computing contract text commitments.
+pub const CONTRACT_TEXT_TAG: &str = "urn:lnp-bp:rgb:contract-text#2026-10-18";
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum TextError {
+    /// text is empty.
+    Empty,
+
+    /// text is {0} bytes long, exceeding the maximum of 65535 bytes.
+    TooLong(usize),
+
+    /// text contains control character U+{code:04X} at position {pos}.
+    Control { code: u32, pos: usize },
+}
+
+/// Markup language of a contract text.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum TextMarkup {
+    #[default]
+    Plain = 0,
+    Markdown = 1,
+}
+
+/// Long multi-line text, like terms and conditions or an asset description.
+///
+/// Unlike [`crate::Details`], the text may contain any Unicode characters and line breaks, up to
+/// 64 kiB. Line endings are normalized to `\n` and control characters other than line feeds and
+/// tabs are rejected, so the same text always produces the same commitment. Decoding fails on
+/// text which is not normalized, while serde deserialization normalizes it.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictEncode)]
+#[strict_type(lib = LIB_NAME_RGB_CONTRACT)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+pub struct ContractText {
+    markup: TextMarkup,
+    text: Confined<String, 1, U16MAX>,
+}
+
+impl StrictDumb for ContractText {
+    fn strict_dumb() -> Self {
+        ContractText {
+            markup: strict_dumb!(),
+            text: Confined::from_checked(s!("?")),
+        }
+    }
+}
+impl StrictSerialize for ContractText {}
+impl StrictDeserialize for ContractText {}
+
+impl StrictDecode for ContractText {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_struct(|r| {
+            let markup = r.read_field(fname!("markup"))?;
+            let text: Confined<String, 1, U16MAX> = r.read_field(fname!("text"))?;
+            Self::check(&text).map_err(|err| DecodeError::DataIntegrityError(err.to_string()))?;
+            Ok(ContractText { markup, text })
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ContractText {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Text {
+            markup: TextMarkup,
+            text: String,
+        }
+        let Text { markup, text } = Text::deserialize(deserializer)?;
+        ContractText::new(markup, &text).map_err(serde::de::Error::custom)
+    }
+}
+
+impl ContractText {
+    /// Constructs validated text, normalizing its line endings.
+    pub fn new(markup: TextMarkup, text: &str) -> Result<Self, TextError> {
+        let text = text.replace("\r\n", "\n");
+        Self::check(&text)?;
+        let len = text.len();
+        let text = Confined::try_from(text).map_err(|_| match len {
+            0 => TextError::Empty,
+            len => TextError::TooLong(len),
+        })?;
+        Ok(ContractText { markup, text })
+    }
+
+    fn check(text: &str) -> Result<(), TextError> {
+        if let Some((pos, c)) = text
+            .chars()
+            .enumerate()
+            .find(|(_, c)| c.is_control() && !matches!(c, '\n' | '\t'))
+        {
+            return Err(TextError::Control { code: c as u32, pos });
+        }
+        Ok(())
+    }
+
+    pub fn plain(text: &str) -> Result<Self, TextError> { Self::new(TextMarkup::Plain, text) }
+
+    pub fn markdown(text: &str) -> Result<Self, TextError> { Self::new(TextMarkup::Markdown, text) }
+
+    pub fn markup(&self) -> TextMarkup { self.markup }
+
+    pub fn as_str(&self) -> &str { self.text.as_str() }
+
+    /// Computes tagged SHA-256 commitment to the text and its markup, which can be put into a
+    /// contract while the text itself is stored off-chain.
+    pub fn commitment(&self) -> Bytes32 {
+        Bytes32::from_byte_array(
+            Sha256::from_tag(CONTRACT_TEXT_TAG)
+                .with_raw(&[self.markup as u8])
+                .with_raw(self.text.as_bytes())
+                .finish(),
+        )
+    }
+
+    /// Checks that the text matches a commitment.
+    pub fn verify(&self, commitment: Bytes32) -> bool { self.commitment() == commitment }
+}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::DeserializeError;
+
+    use super::*;
+
+    #[test]
+    fn normalization() {
+        let text = ContractText::markdown("# Terms\r\n\r\n1. Be nice\n\t2. Tabs are fine").unwrap();
+        assert_eq!(text.as_str(), "# Terms\n\n1. Be nice\n\t2. Tabs are fine");
+        assert_eq!(ContractText::plain(""), Err(TextError::Empty));
+        assert_eq!(ContractText::plain(&"x".repeat(0x10000)), Err(TextError::TooLong(0x10000)));
+        assert_eq!(ContractText::plain("bell\u{7}"), Err(TextError::Control { code: 7, pos: 4 }));
+        assert_eq!(ContractText::plain("lone\rreturn"), Err(TextError::Control { code: 0x0D, pos: 4 }));
+        assert!(ContractText::plain("Условия использования ✓").is_ok());
+    }
+
+    #[test]
+    fn commitment() {
+        let text = ContractText::markdown("Terms\r\nof use").unwrap();
+        let commitment = text.commitment();
+        assert!(ContractText::markdown("Terms\nof use")
+            .unwrap()
+            .verify(commitment));
+        assert!(!ContractText::plain("Terms\nof use")
+            .unwrap()
+            .verify(commitment));
+        assert!(!ContractText::markdown("Terms of use")
+            .unwrap()
+            .verify(commitment));
+
+        let data = text.to_strict_serialized::<0x10010>().unwrap();
+        assert_eq!(ContractText::from_strict_serialized::<0x10010>(data).unwrap(), text);
+    }
+
+    #[test]
+    fn decoding() {
+        // markdown text "a\r\nb"
+        let data = Confined::from_checked(vec![1, 4, 0, b'a', b'\r', b'\n', b'b']);
+        assert!(matches!(
+            ContractText::from_strict_serialized::<0x10010>(data),
+            Err(DeserializeError::Decode(DecodeError::DataIntegrityError(_)))
+        ));
+
+        #[cfg(feature = "serde")]
+        {
+            let text: ContractText = serde_json::from_str(r#"{"markup":"markdown","text":"a\r\nb"}"#).unwrap();
+            assert_eq!(text, ContractText::markdown("a\nb").unwrap());
+            assert!(serde_json::from_str::<ContractText>(r#"{"markup":"plain","text":"bell\u0007"}"#).is_err());
+            assert!(serde_json::from_str::<ContractText>(r#"{"markup":"plain","text":""}"#).is_err());
+        }
+    }
+}
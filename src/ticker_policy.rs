@@ -0,0 +1,289 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+
+use crate::Ticker;
+
+/// Active ISO 4217 currency codes, including precious metals and special drawing rights.
+pub const ISO_4217_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT", "BGN", "BHD", "BIF",
+    "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD", "CDF", "CHF", "CLP", "CNY", "COP", "CRC",
+    "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS",
+    "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD",
+    "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL",
+    "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MYR", "MZN", "NAD",
+    "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD",
+    "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP",
+    "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XCD", "XDR", "XOF", "XPD", "XPF", "XPT", "YER", "ZAR", "ZMW", "ZWL",
+];
+
+/// Tickers of major crypto assets and stablecoins.
+pub const CRYPTO_TICKERS: &[&str] = &[
+    "BTC", "XBT", "SAT", "SATS", "LBTC", "ETH", "USDT", "USDC", "DAI", "BNB", "XRP", "SOL", "ADA", "DOGE", "TRX",
+    "DOT", "MATIC", "LTC", "SHIB", "AVAX", "LINK", "BCH", "XLM", "XMR", "ATOM", "UNI", "ETC", "TON",
+];
+
+/// Groups of characters which are easily confused visually; each character is mapped to the
+/// first character of its group when comparing tickers.
+pub const TICKER_CONFUSABLES: &[&str] = &["O0", "I1L", "S5"];
+
+/// Reason for reserving a ticker.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[display(lowercase)]
+pub enum ReservedCategory {
+    /// ISO 4217 fiat currency, precious metal or other currency-like unit.
+    Fiat,
+    /// Well-known crypto asset.
+    Crypto,
+    /// Symbol reserved by the wallet or the user.
+    Custom,
+}
+
+/// Risk of a ticker being used to impersonate another asset.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[display(lowercase)]
+pub enum TickerRisk {
+    /// Ticker doesn't resemble any reserved symbol.
+    #[default]
+    Low,
+    /// Ticker is a reserved symbol; the contract may be genuine, but its issuer must be verified.
+    Elevated,
+    /// Ticker visually imitates a reserved symbol without matching it; this is a common phishing
+    /// technique.
+    High,
+}
+
+/// Match of a ticker against a reserved symbol.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[display(doc_comments)]
+pub enum TickerFinding {
+    /// ticker is the reserved {category} symbol {symbol}.
+    Reserved { symbol: String, category: ReservedCategory },
+
+    /// ticker is visually confusable with the reserved {category} symbol {symbol}.
+    Confusable { symbol: String, category: ReservedCategory },
+}
+
+impl TickerFinding {
+    /// Risk implied by the finding.
+    pub fn risk(&self) -> TickerRisk {
+        match self {
+            TickerFinding::Reserved { .. } => TickerRisk::Elevated,
+            TickerFinding::Confusable { .. } => TickerRisk::High,
+        }
+    }
+}
+
+/// Result of checking a ticker against a [`TickerPolicy`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TickerAssessment {
+    pub ticker: Ticker,
+    pub findings: Vec<TickerFinding>,
+}
+
+impl TickerAssessment {
+    /// The highest risk among all findings.
+    pub fn risk(&self) -> TickerRisk {
+        self.findings
+            .iter()
+            .map(TickerFinding::risk)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Policy for checking tickers of imported contracts against a list of reserved symbols.
+///
+/// Symbols are compared case-insensitively, like [`Ticker`] values themselves.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TickerPolicy {
+    reserved: BTreeMap<String, ReservedCategory>,
+    confusables: BTreeMap<char, char>,
+}
+
+impl Default for TickerPolicy {
+    fn default() -> Self { Self::standard() }
+}
+
+impl TickerPolicy {
+    /// Constructs policy without reserved symbols, using [`TICKER_CONFUSABLES`].
+    pub fn empty() -> Self {
+        let mut policy = TickerPolicy { reserved: none!(), confusables: none!() };
+        for group in TICKER_CONFUSABLES {
+            policy.add_confusables(group);
+        }
+        policy
+    }
+
+    /// Constructs policy reserving [`ISO_4217_CODES`] and [`CRYPTO_TICKERS`].
+    pub fn standard() -> Self {
+        let mut policy = Self::empty();
+        for code in ISO_4217_CODES {
+            policy.reserve(code, ReservedCategory::Fiat);
+        }
+        for ticker in CRYPTO_TICKERS {
+            policy.reserve(ticker, ReservedCategory::Crypto);
+        }
+        policy
+    }
+
+    /// Reserves a symbol, replacing its previous category, if any.
+    pub fn reserve(&mut self, symbol: &str, category: ReservedCategory) {
+        self.reserved.insert(symbol.to_uppercase(), category);
+    }
+
+    /// Removes symbol from the reserved list, returning its category.
+    pub fn unreserve(&mut self, symbol: &str) -> Option<ReservedCategory> {
+        self.reserved.remove(&symbol.to_uppercase())
+    }
+
+    /// Declares a group of visually confusable characters. Groups sharing a character are merged,
+    /// so confusability is transitive.
+    pub fn add_confusables(&mut self, group: &str) {
+        let mut chars = group.chars().flat_map(char::to_uppercase);
+        let Some(first) = chars.next() else {
+            return;
+        };
+        let canonical = self.canonical_char(first);
+        self.confusables.insert(first, canonical);
+        for c in chars {
+            let previous = self.canonical_char(c);
+            if previous != canonical {
+                // Re-point all members of the group being merged, keeping the map flat.
+                for target in self.confusables.values_mut() {
+                    if *target == previous {
+                        *target = canonical;
+                    }
+                }
+            }
+            self.confusables.insert(c, canonical);
+        }
+    }
+
+    pub fn is_reserved(&self, symbol: &str) -> bool { self.reserved.contains_key(&symbol.to_uppercase()) }
+
+    /// Lists reserved symbols with their categories.
+    pub fn reserved(&self) -> impl Iterator<Item = (&str, ReservedCategory)> {
+        self.reserved
+            .iter()
+            .map(|(symbol, category)| (symbol.as_str(), *category))
+    }
+
+    /// Checks the ticker against the reserved symbols.
+    pub fn assess(&self, ticker: &Ticker) -> TickerAssessment {
+        let symbol = ticker.as_str().to_uppercase();
+        let skeleton = self.skeleton(&symbol);
+        let findings = self
+            .reserved
+            .iter()
+            .filter_map(|(reserved, category)| {
+                if *reserved == symbol {
+                    Some(TickerFinding::Reserved { symbol: reserved.clone(), category: *category })
+                } else if self.skeleton(reserved) == skeleton {
+                    Some(TickerFinding::Confusable { symbol: reserved.clone(), category: *category })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        TickerAssessment { ticker: ticker.clone(), findings }
+    }
+
+    fn canonical_char(&self, c: char) -> char { self.confusables.get(&c).copied().unwrap_or(c) }
+
+    fn skeleton(&self, symbol: &str) -> String {
+        symbol
+            .chars()
+            .flat_map(char::to_uppercase)
+            .map(|c| self.canonical_char(c))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn assess(ticker: &str) -> TickerAssessment { TickerPolicy::standard().assess(&Ticker::from_str(ticker).unwrap()) }
+
+    #[test]
+    fn reserved() {
+        let assessment = assess("usdt");
+        assert_eq!(assessment.risk(), TickerRisk::Elevated);
+        assert_eq!(assessment.findings, vec![TickerFinding::Reserved {
+            symbol: s!("USDT"),
+            category: ReservedCategory::Crypto
+        }]);
+        assert_eq!(assess("EUR").findings[0].to_string(), "ticker is the reserved fiat symbol EUR.");
+        assert_eq!(assess("MYTOKEN").risk(), TickerRisk::Low);
+    }
+
+    #[test]
+    fn confusable() {
+        let assessment = assess("U5DT");
+        assert_eq!(assessment.risk(), TickerRisk::High);
+        assert_eq!(assessment.findings, vec![TickerFinding::Confusable {
+            symbol: s!("USDT"),
+            category: ReservedCategory::Crypto
+        }]);
+        assert_eq!(assess("B1C").risk(), TickerRisk::Low);
+        assert_eq!(assess("D0GE").risk(), TickerRisk::High);
+        assert_eq!(assess("SHlB").risk(), TickerRisk::High);
+    }
+
+    #[test]
+    fn merged_groups() {
+        let mut policy = TickerPolicy::standard();
+        policy.add_confusables("ZS");
+        let ticker = |s: &str| Ticker::from_str(s).unwrap();
+        assert_eq!(policy.assess(&ticker("U5DT")).risk(), TickerRisk::High);
+        assert_eq!(policy.assess(&ticker("UZDT")).risk(), TickerRisk::High);
+        assert_eq!(policy.assess(&ticker("USDT")).risk(), TickerRisk::Elevated);
+
+        policy.add_confusables("2Z");
+        assert_eq!(policy.assess(&ticker("U2DT")).risk(), TickerRisk::High);
+        assert_eq!(policy.assess(&ticker("U5DT")).risk(), TickerRisk::High);
+    }
+
+    #[test]
+    fn configuration() {
+        let mut policy = TickerPolicy::empty();
+        let ticker = Ticker::from_str("ACME").unwrap();
+        assert_eq!(policy.assess(&ticker).risk(), TickerRisk::Low);
+        policy.reserve("acme", ReservedCategory::Custom);
+        assert_eq!(policy.assess(&ticker).risk(), TickerRisk::Elevated);
+
+        let imitation = Ticker::from_str("ACM3").unwrap();
+        assert_eq!(policy.assess(&imitation).risk(), TickerRisk::Low);
+        policy.add_confusables("E3");
+        assert_eq!(policy.assess(&imitation).risk(), TickerRisk::High);
+
+        assert_eq!(policy.unreserve("ACME"), Some(ReservedCategory::Custom));
+        assert!(!policy.is_reserved("acme"));
+        assert_eq!(policy.assess(&imitation).risk(), TickerRisk::Low);
+    }
+}
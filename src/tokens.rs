@@ -0,0 +1,440 @@
+// Collection of the standard RGB smart contract interface
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by RGB Consortium members & contributors
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2025 RGB Consortium members & contributors
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::confinement::{self, SmallVec};
+use amplify::Wrapper;
+use strict_encoding::{DecodeError, ReadStruct, StrictDecode, StrictDeserialize, StrictSerialize, TypedRead};
+
+use crate::{NftAllocations, OwnedNft, TokenFractions, TokenNo, LIB_NAME_RGB21};
+
+/// Maximum number of tokens which can be expanded into a single allocation list.
+pub const TOKEN_SET_MAX_ALLOCATIONS: u64 = 0x10_0000;
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum TokenSetError {
+    /// invalid token number '{value}' at position {pos}.
+    InvalidNumber { value: String, pos: usize },
+
+    /// range at position {pos} has its start after its end.
+    ReversedRange { pos: usize },
+
+    /// token set ranges are not sorted, overlap or are adjacent to each other.
+    NonCanonical,
+
+    /// token set contains {0} tokens, which exceeds the allocation list limit of
+    /// {TOKEN_SET_MAX_ALLOCATIONS}.
+    TooManyTokens(u64),
+
+    /// too many ranges in the token set; {0}
+    #[from]
+    Confinement(confinement::Error),
+}
+
+/// Inclusive range of token numbers.
+///
+/// The range start never exceeds its end; this is checked on construction and during
+/// deserialization.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+pub struct TokenRange {
+    first: TokenNo,
+    last: TokenNo,
+}
+
+impl StrictDecode for TokenRange {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_struct(|r| {
+            let first: TokenNo = r.read_field(fname!("first"))?;
+            let last: TokenNo = r.read_field(fname!("last"))?;
+            TokenRange::new(first, last)
+                .ok_or_else(|| DecodeError::DataIntegrityError(format!("token range {first}-{last} is reversed")))
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TokenRange {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Range {
+            first: TokenNo,
+            last: TokenNo,
+        }
+        let Range { first, last } = Range::deserialize(deserializer)?;
+        TokenRange::new(first, last)
+            .ok_or_else(|| serde::de::Error::custom(format!("token range {first}-{last} is reversed")))
+    }
+}
+
+impl TokenRange {
+    /// Constructs range, returning `None` if `first` is greater than `last`.
+    pub fn new(first: impl Into<TokenNo>, last: impl Into<TokenNo>) -> Option<Self> {
+        let (first, last) = (first.into(), last.into());
+        (first <= last).then_some(TokenRange { first, last })
+    }
+
+    pub fn single(no: impl Into<TokenNo>) -> Self {
+        let no = no.into();
+        TokenRange { first: no, last: no }
+    }
+
+    pub fn first(&self) -> TokenNo { self.first }
+
+    pub fn last(&self) -> TokenNo { self.last }
+
+    /// Number of tokens in the range.
+    pub fn len(&self) -> u64 { (self.last.into_inner() - self.first.into_inner()) as u64 + 1 }
+
+    /// Ranges always contain at least a single token.
+    pub fn is_empty(&self) -> bool { false }
+
+    pub fn contains(&self, no: TokenNo) -> bool { self.first <= no && no <= self.last }
+
+    pub fn iter(&self) -> impl Iterator<Item = TokenNo> {
+        (self.first.into_inner()..=self.last.into_inner()).map(TokenNo::from)
+    }
+}
+
+impl Display for TokenRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+/// Compact set of token numbers, represented as a list of ranges.
+///
+/// Ranges are kept sorted, non-overlapping and non-adjacent, such that each set has a single
+/// representation. Since this can't be enforced during strict decoding, sets received from
+/// untrusted sources must be checked with [`TokenSet::validate`].
+///
+/// Textual form is a comma-separated list of token numbers and inclusive ranges, like
+/// `1-100,205,300-310`, without whitespace. Parsing accepts ranges in any order, merging them.
+#[derive(Wrapper, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB21)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct TokenSet(SmallVec<TokenRange>);
+
+impl StrictSerialize for TokenSet {}
+impl StrictDeserialize for TokenSet {}
+
+impl TokenSet {
+    pub fn new() -> Self { Self::default() }
+
+    /// Constructs set from arbitrary ranges, merging them.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = TokenRange>) -> Result<Self, TokenSetError> {
+        let mut ranges = ranges.into_iter().collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.first);
+        let mut merged = Vec::<TokenRange>::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.first.into_inner() <= last.last.into_inner().saturating_add(1) => {
+                    last.last = last.last.max(range.last);
+                }
+                _ => merged.push(range),
+            }
+        }
+        Ok(Self(SmallVec::try_from(merged)?))
+    }
+
+    /// Constructs set from individual token numbers.
+    pub fn from_tokens(tokens: impl IntoIterator<Item = TokenNo>) -> Result<Self, TokenSetError> {
+        Self::from_ranges(tokens.into_iter().map(TokenRange::single))
+    }
+
+    /// Constructs set of the tokens allocated by the provided allocations.
+    pub fn from_owned<'a>(allocations: impl IntoIterator<Item = &'a OwnedNft>) -> Result<Self, TokenSetError> {
+        Self::from_tokens(allocations.into_iter().map(|nft| nft.token_no))
+    }
+
+    /// Checks that the ranges are sorted, non-overlapping and non-adjacent.
+    pub fn validate(&self) -> Result<(), TokenSetError> {
+        let canonical = self
+            .0
+            .windows(2)
+            .all(|pair| match pair[0].last.into_inner().checked_add(1) {
+                None => false,
+                Some(next) => next < pair[1].first.into_inner(),
+            });
+        if !canonical {
+            return Err(TokenSetError::NonCanonical);
+        }
+        Ok(())
+    }
+
+    /// Total number of tokens in the set.
+    pub fn count(&self) -> u64 { self.0.iter().map(TokenRange::len).sum() }
+
+    pub fn contains(&self, no: TokenNo) -> bool {
+        self.0
+            .binary_search_by(|range| {
+                if range.last < no {
+                    Ordering::Less
+                } else if range.first > no {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Iterates over all token numbers in the set in ascending order.
+    pub fn tokens(&self) -> impl Iterator<Item = TokenNo> + '_ { self.0.iter().flat_map(TokenRange::iter) }
+
+    pub fn union(&self, other: &Self) -> Result<Self, TokenSetError> {
+        Self::from_ranges(self.0.iter().chain(other.0.iter()).copied())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Result<Self, TokenSetError> {
+        let (mut a, mut b) = (self.0.iter().peekable(), other.0.iter().peekable());
+        let mut ranges = vec![];
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if let Some(range) = TokenRange::new(x.first.max(y.first), x.last.min(y.last)) {
+                ranges.push(range);
+            }
+            if x.last < y.last {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Ok(Self(SmallVec::try_from(ranges)?))
+    }
+
+    pub fn difference(&self, other: &Self) -> Result<Self, TokenSetError> {
+        let mut ranges = vec![];
+        let mut others = other.0.iter().peekable();
+        for range in self.0.iter() {
+            let mut first = range.first.into_inner();
+            let last = range.last.into_inner();
+            let mut exhausted = false;
+            while let Some(cut) = others.peek() {
+                if cut.last.into_inner() < first {
+                    others.next();
+                    continue;
+                }
+                if cut.first.into_inner() > last {
+                    break;
+                }
+                if cut.first.into_inner() > first {
+                    ranges.push(TokenRange {
+                        first: first.into(),
+                        last: (cut.first.into_inner() - 1).into(),
+                    });
+                }
+                if cut.last.into_inner() >= last {
+                    exhausted = true;
+                    break;
+                }
+                first = cut.last.into_inner() + 1;
+                others.next();
+            }
+            if !exhausted {
+                ranges.push(TokenRange { first: first.into(), last: last.into() });
+            }
+        }
+        Ok(Self(SmallVec::try_from(ranges)?))
+    }
+
+    /// Produces allocations of the given number of fractions for each of the tokens in the set.
+    ///
+    /// The iterator is lazy and may yield up to 2^32 items, so callers collecting it must bound
+    /// the number of items themselves, or check [`Self::count`] first.
+    pub fn owned_nfts(&self, fractions: impl Into<TokenFractions>) -> impl Iterator<Item = OwnedNft> + '_ {
+        let fractions = fractions.into();
+        self.tokens()
+            .map(move |token_no| OwnedNft { token_no, fractions })
+    }
+
+    /// Produces allocation list with the given number of fractions for each of the tokens in the
+    /// set. Fails if the set contains more than [`TOKEN_SET_MAX_ALLOCATIONS`] tokens, or if it is
+    /// not canonical and lists some of the tokens more than once.
+    pub fn to_allocations(&self, fractions: impl Into<TokenFractions>) -> Result<NftAllocations, TokenSetError> {
+        let count = self.count();
+        if count > TOKEN_SET_MAX_ALLOCATIONS {
+            return Err(TokenSetError::TooManyTokens(count));
+        }
+        NftAllocations::with(self.owned_nfts(fractions)).map_err(|_| TokenSetError::NonCanonical)
+    }
+}
+
+impl TryFrom<&NftAllocations> for TokenSet {
+    type Error = TokenSetError;
+
+    fn try_from(allocations: &NftAllocations) -> Result<Self, Self::Error> {
+        Self::from_tokens(allocations.keys().copied())
+    }
+}
+
+impl Display for TokenSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, range) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            Display::fmt(range, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TokenSet {
+    type Err = TokenSetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+        let parse = |value: &str, pos: usize| -> Result<TokenNo, TokenSetError> {
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(TokenSetError::InvalidNumber { value: value.to_owned(), pos });
+            }
+            value
+                .parse::<u32>()
+                .map(TokenNo::from)
+                .map_err(|_| TokenSetError::InvalidNumber { value: value.to_owned(), pos })
+        };
+        let mut ranges = vec![];
+        let mut pos = 0;
+        for item in s.split(',') {
+            let range = match item.split_once('-') {
+                None => TokenRange::single(parse(item, pos)?),
+                Some((first, last)) => {
+                    let first = parse(first, pos)?;
+                    let last = parse(last, pos + item.len() - last.len())?;
+                    TokenRange::new(first, last).ok_or(TokenSetError::ReversedRange { pos })?
+                }
+            };
+            ranges.push(range);
+            pos += item.len() + 1;
+        }
+        Self::from_ranges(ranges)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::DeserializeError;
+
+    use super::*;
+
+    fn set(s: &str) -> TokenSet { TokenSet::from_str(s).unwrap() }
+
+    #[test]
+    fn notation() {
+        let tokens = set("300-310,1-100,205,101,99");
+        assert_eq!(tokens.to_string(), "1-101,205,300-310");
+        assert_eq!(tokens.count(), 113);
+        assert!(tokens.contains(205.into()));
+        assert!(!tokens.contains(206.into()));
+        assert_eq!(set(""), TokenSet::new());
+        assert_eq!(set("0-4294967295").count(), 1 << 32);
+
+        assert_eq!(TokenSet::from_str("5-3"), Err(TokenSetError::ReversedRange { pos: 0 }));
+        assert_eq!(TokenSet::from_str("1,2-x"), Err(TokenSetError::InvalidNumber { value: s!("x"), pos: 4 }));
+        assert_eq!(TokenSet::from_str("1,,2"), Err(TokenSetError::InvalidNumber { value: s!(""), pos: 2 }));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set("1-10,20-30");
+        let b = set("5-25,40");
+        assert_eq!(a.union(&b).unwrap().to_string(), "1-30,40");
+        assert_eq!(a.intersection(&b).unwrap().to_string(), "5-10,20-25");
+        assert_eq!(a.difference(&b).unwrap().to_string(), "1-4,26-30");
+        assert_eq!(b.difference(&a).unwrap().to_string(), "11-19,40");
+        assert_eq!(a.difference(&a).unwrap(), TokenSet::new());
+        assert_eq!(
+            set("0-4294967295")
+                .difference(&set("1-4294967294"))
+                .unwrap()
+                .to_string(),
+            "0,4294967295"
+        );
+        assert_eq!(set("1-3").tokens().collect::<Vec<_>>(), vec![TokenNo::from(1), TokenNo::from(2), TokenNo::from(3)]);
+    }
+
+    #[test]
+    fn allocations() {
+        let tokens = set("5-7");
+        let allocations = tokens.to_allocations(1).unwrap();
+        assert_eq!(allocations.to_string(), "1@5,1@6,1@7");
+        assert_eq!(TokenSet::try_from(&allocations).unwrap(), tokens);
+        let owned = tokens.owned_nfts(2).collect::<Vec<_>>();
+        assert_eq!(TokenSet::from_owned(&owned).unwrap(), tokens);
+
+        assert_eq!(set("0-4294967295").to_allocations(1), Err(TokenSetError::TooManyTokens(1 << 32)));
+        let max = TokenRange::new(1, TOKEN_SET_MAX_ALLOCATIONS as u32).unwrap();
+        assert_eq!(
+            TokenSet::from_ranges([max])
+                .unwrap()
+                .to_allocations(1)
+                .unwrap()
+                .len() as u64,
+            TOKEN_SET_MAX_ALLOCATIONS
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let data = set("1-3,7").to_strict_serialized::<0xFFFF>().unwrap();
+        let decoded = TokenSet::from_strict_serialized::<0xFFFF>(data).unwrap();
+        assert_eq!(decoded.validate(), Ok(()));
+
+        let overlapping = TokenSet::from(SmallVec::from_checked(vec![
+            TokenRange::new(1, 3).unwrap(),
+            TokenRange::new(4, 5).unwrap(),
+        ]));
+        assert_eq!(overlapping.validate(), Err(TokenSetError::NonCanonical));
+        let repeated = TokenSet::from(SmallVec::from_checked(vec![
+            TokenRange::new(1, 3).unwrap(),
+            TokenRange::new(2, 5).unwrap(),
+        ]));
+        assert_eq!(repeated.to_allocations(1), Err(TokenSetError::NonCanonical));
+
+        // ranges 5-4294967295, 0-10
+        let unsorted = SmallVec::from_checked(vec![2, 0, 5, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 10, 0, 0, 0]);
+        let decoded = TokenSet::from_strict_serialized::<0xFFFF>(unsorted).unwrap();
+        assert_eq!(decoded.validate(), Err(TokenSetError::NonCanonical));
+        assert_eq!(set("0-4294967295").validate(), Ok(()));
+
+        // single range 3-1
+        let reversed = SmallVec::from_checked(vec![1, 0, 3, 0, 0, 0, 1, 0, 0, 0]);
+        assert!(matches!(
+            TokenSet::from_strict_serialized::<0xFFFF>(reversed),
+            Err(DeserializeError::Decode(DecodeError::DataIntegrityError(_)))
+        ));
+        #[cfg(feature = "serde")]
+        assert!(serde_json::from_str::<TokenRange>(r#"{"first":3,"last":1}"#).is_err());
+    }
+}
@@ -24,15 +24,20 @@ use strict_types::stl::std_stl;
 use strict_types::{LibBuilder, SemId, SymbolicSys, SystemBuilder, TypeLib, TypeSystem};
 
 use crate::{
-    Amount, AssetName, Details, EmbeddedMedia, Nft, NftSpec, OwnedNft, Precision, ProofOfReserves, Ticker,
-    LIB_NAME_RGB21, LIB_NAME_RGB_CONTRACT,
+    AggregateReserves, Amount, AssetName, AttachmentV1, ChunkProof, ChunkedMedia, ContractTerms, ContractText, Details,
+    DigestAlgo, EmbeddedMedia, Engraving, IntlAssetName, Layer1Ptr, MediaLocators, Nft, NftAttributes, NftCollection,
+    NftSpec, OwnedNft, Precision, ProofOfReserves, ProofOfReservesV1, ReserveProof, Royalty, SftAllocation, SftClasses,
+    Ticker, TokenSet, LIB_NAME_RGB21, LIB_NAME_RGB_CONTRACT, LIB_NAME_RGB_SFT,
 };
 
 /// Strict types id for the library providing data types for RGB contracts.
-pub const LIB_ID_RGB_INTERFACES: &str = "stl:SwzsMZmH-_Bp~u1Y-sYRyzR9-sj3ZgR7-JNCrNuP-PudeT5c#viva-comrade-bernard";
+pub const LIB_ID_RGB_INTERFACES: &str = "stl:JBK1IuPC-8Wndgly-VUCCujj-u2JmsOL-z46buph-AQka3qE#private-janet-chamber";
 
 /// Strict types id for the library providing data types for RGB21.
-pub const LIB_ID_RGB21: &str = "stl:hS_TcpfI-JCz36Es-E8NtlsS-bG0A68X-Te6ouRs-7_FNDhE#prelude-chicago-filter";
+pub const LIB_ID_RGB21: &str = "stl:Pqcs52Qv-_aSQzI2-kv9RppJ-DCGhcNC-~c_WXTr-aJMahj4#gregory-bread-fuji";
+
+/// Strict types id for the library providing data types for semi-fungible tokens.
+pub const LIB_ID_RGB_SFT: &str = "stl:aMV4fOdM-tpUYS0v-55CzExR-HcUN7ct-nDUfLNL-yry7YmY#image-bless-halt";
 
 pub fn rgb_contract_stl() -> TypeLib {
     LibBuilder::with(libname!(LIB_NAME_RGB_CONTRACT), [
@@ -43,8 +48,16 @@ pub fn rgb_contract_stl() -> TypeLib {
     .transpile::<Precision>()
     .transpile::<Ticker>()
     .transpile::<AssetName>()
+    .transpile::<IntlAssetName>()
     .transpile::<Details>()
+    .transpile::<ContractText>()
+    .transpile::<ContractTerms>()
+    .transpile::<DigestAlgo>()
     .transpile::<ProofOfReserves>()
+    .transpile::<ProofOfReservesV1>()
+    .transpile::<ReserveProof>()
+    .transpile::<AggregateReserves>()
+    .transpile::<Layer1Ptr>()
     .compile()
     .expect("invalid common types library")
 }
@@ -60,10 +73,30 @@ pub fn rgb21_stl() -> TypeLib {
     .transpile::<OwnedNft>()
     .transpile::<NftSpec>()
     .transpile::<EmbeddedMedia>()
+    .transpile::<AttachmentV1>()
+    .transpile::<MediaLocators>()
+    .transpile::<ChunkedMedia>()
+    .transpile::<ChunkProof>()
+    .transpile::<NftCollection>()
+    .transpile::<NftAttributes>()
+    .transpile::<TokenSet>()
+    .transpile::<Royalty>()
+    .transpile::<Engraving>()
     .compile()
     .expect("invalid common types library")
 }
 
+pub fn rgb_sft_stl() -> TypeLib {
+    LibBuilder::with(libname!(LIB_NAME_RGB_SFT), [
+        std_stl().to_dependency_types(),
+        rgb_contract_stl().to_dependency_types(),
+    ])
+    .transpile::<SftClasses>()
+    .transpile::<SftAllocation>()
+    .compile()
+    .expect("invalid semi-fungible types library")
+}
+
 #[derive(Debug)]
 pub struct CommonTypes(SymbolicSys);
 
@@ -143,6 +176,47 @@ impl Rgb21Types {
     }
 }
 
+#[derive(Debug)]
+pub struct SftTypes(SymbolicSys);
+
+impl Default for SftTypes {
+    fn default() -> Self { SftTypes::new() }
+}
+
+impl SftTypes {
+    pub fn new() -> Self {
+        Self(
+            SystemBuilder::new()
+                .import(std_stl())
+                .unwrap()
+                .import(rgb_contract_stl())
+                .unwrap()
+                .import(bp_tx_stl())
+                .unwrap()
+                .import(rgb_sft_stl())
+                .unwrap()
+                .finalize()
+                .unwrap(),
+        )
+    }
+
+    pub fn type_system(&self) -> TypeSystem {
+        let types = rgb_sft_stl()
+            .types
+            .into_iter()
+            .chain(rgb_contract_stl().types)
+            .map(|(tn, ty)| ty.sem_id_named(&tn));
+        self.0.as_types().extract(types).unwrap()
+    }
+
+    pub fn get(&self, name: &'static str) -> SemId {
+        *self
+            .0
+            .resolve(name)
+            .unwrap_or_else(|| panic!("type '{name}' is absent in RGB semi-fungible type library"))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -158,4 +232,10 @@ mod test {
         let lib = rgb21_stl();
         assert_eq!(lib.id().to_string(), LIB_ID_RGB21);
     }
+
+    #[test]
+    fn sft_lib_id() {
+        let lib = rgb_sft_stl();
+        assert_eq!(lib.id().to_string(), LIB_ID_RGB_SFT);
+    }
 }